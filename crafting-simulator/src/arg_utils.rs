@@ -2,7 +2,7 @@ use crate::{model::SimulatorRecipe, presets::Presets as preset};
 use color_eyre::eyre::{eyre, Result};
 use ff14_data::{
     lookup::{ItemLookup, RecipeLookup},
-    model::Food,
+    model::{Food, Language},
 };
 
 // TODO: might be nice to dedupe more arg handling knowledge here
@@ -11,9 +11,7 @@ use ff14_data::{
 
 pub fn recipe_from_arg_value(value: &str) -> Result<SimulatorRecipe> {
     let item_lookup = &ItemLookup::from_embedded()?;
-    let item = item_lookup
-        .item_by_name_opt(value)
-        .ok_or_else(|| eyre!("No item found with name {}", value))?;
+    let item = item_lookup.find_item_by_name(value, Language::English)?;
 
     let recipe_lookup = &RecipeLookup::from_embedded()?;
     let recipe = recipe_lookup
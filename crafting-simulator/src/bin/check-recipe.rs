@@ -23,9 +23,7 @@ async fn main() -> Result<()> {
     // read list of crafting steps from stdin
     let mut steps = String::new();
     std::io::stdin().read_to_string(&mut steps)?;
-    // TODO: can we remove the requirement for run_steps taking a 'static str to avoid this
-    // .leak()?
-    let steps = steps.leak().trim().lines().collect_vec();
+    let steps = steps.trim().lines().collect_vec();
 
     for (job, mut player) in config {
         // TODO: more deduping with crafting-simulator bin
@@ -0,0 +1,366 @@
+use color_eyre::{eyre::eyre, Result};
+use crafting_simulator::{
+    arg_utils::{food_from_arg_value, potion_from_arg_value, recipe_from_arg_value},
+    buffs::apply_buff_hq,
+    config,
+    model::{CraftStatus, CraftingState},
+    session::{ActionPreview, CraftingSession},
+};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use itertools::Itertools;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Gauge, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::{fs, path::Path};
+
+struct App {
+    title: String,
+    session: CraftingSession,
+    previews: Vec<ActionPreview>,
+    selected: ListState,
+    message: String,
+}
+
+impl App {
+    fn new(title: String, session: CraftingSession) -> App {
+        let previews = session.preview_all();
+        App {
+            title,
+            session,
+            previews,
+            selected: ListState::default().with_selected(Some(0)),
+            message: String::new(),
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.previews = self.session.preview_all();
+    }
+
+    fn apply_selected(&mut self) {
+        let Some(preview) = self.selected.selected().and_then(|i| self.previews.get(i)) else {
+            return;
+        };
+        let action = preview.action;
+        self.message = match self.session.apply(action) {
+            Ok(()) => format!("applied {}", action),
+            Err(e) => e.to_string(),
+        };
+        self.refresh();
+    }
+
+    fn undo(&mut self) {
+        self.message = match self.session.undo() {
+            Some(action) => format!("undid {}", action),
+            None => "nothing to undo".to_string(),
+        };
+        self.refresh();
+    }
+
+    fn export(&mut self) {
+        let path = Path::new("./macro.txt");
+        self.message = match fs::write(path, self.session.to_macro()) {
+            Ok(()) => format!(
+                "exported {} steps to {}",
+                self.session.steps().len(),
+                path.display()
+            ),
+            Err(e) => format!("failed to export macro: {}", e),
+        };
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = parse_args()?;
+
+    let config = config::read_jobs_from_config(Path::new("./jobs.toml"))?;
+
+    let mut player = config
+        .iter()
+        .find(|(name, _)| *name == args.job_name)
+        .ok_or_else(|| eyre!("Job {} not found in jobs.toml", args.job_name))?
+        .1;
+
+    let food = food_from_arg_value(args.food.as_deref())?;
+    let potion = potion_from_arg_value(args.potion.as_deref())?;
+    let recipe = recipe_from_arg_value(&args.recipe)?;
+
+    if let Some(food) = food {
+        player = apply_buff_hq(&player, food);
+    }
+    if let Some(potion) = potion {
+        player = apply_buff_hq(&player, potion);
+    }
+
+    let title = format!("{} - {}", args.job_name, args.recipe);
+    let mut app = App::new(title, CraftingSession::new(player, recipe));
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+    result?;
+
+    // leave the final sequence in the terminal so that it can be copied into the game
+    println!("{}", app.session.to_macro());
+
+    Ok(())
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => app.selected.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => app.selected.select_next(),
+                KeyCode::Enter | KeyCode::Char(' ') => app.apply_selected(),
+                KeyCode::Backspace | KeyCode::Char('u') => app.undo(),
+                KeyCode::Char('e') => app.export(),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [header_area, main_area, footer_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [state_area, actions_area, steps_area] = Layout::horizontal([
+        Constraint::Percentage(35),
+        Constraint::Percentage(40),
+        Constraint::Percentage(25),
+    ])
+    .areas(main_area);
+
+    let report = app.session.report();
+    frame.render_widget(
+        Line::from(vec![
+            Span::from(app.title.as_str()).bold(),
+            Span::from(format!(
+                "  step {}  {:?}",
+                report.final_state.steps, report.status
+            ))
+            .fg(status_color(report.status)),
+        ]),
+        header_area,
+    );
+
+    draw_state(frame, state_area, &app.session);
+
+    let current = *app.session.state();
+    let finished = app.session.is_finished();
+    let actions = app
+        .previews
+        .iter()
+        .map(|p| action_list_item(p, &current, finished))
+        .collect_vec();
+    frame.render_stateful_widget(
+        List::new(actions)
+            .block(Block::bordered().title("Actions"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> "),
+        actions_area,
+        &mut app.selected,
+    );
+
+    let steps = app
+        .session
+        .steps()
+        .iter()
+        .enumerate()
+        .map(|(i, s)| ListItem::new(format!("{:>2}. {}", i + 1, s)))
+        .collect_vec();
+    let issues = report
+        .issues
+        .iter()
+        .map(|i| ListItem::new(format!("{:?} (step {})", i.issue_type, i.step_index + 1)).red());
+    frame.render_widget(
+        List::new(steps.into_iter().chain(issues)).block(Block::bordered().title("Steps")),
+        steps_area,
+    );
+
+    frame.render_widget(
+        Line::from(format!(
+            "enter: apply  u: undo  e: export to macro.txt  q: quit  {}",
+            app.message
+        ))
+        .dim(),
+        footer_area,
+    );
+}
+
+fn draw_state(frame: &mut Frame, area: Rect, session: &CraftingSession) {
+    let [progress_area, quality_area, durability_area, cp_area, buffs_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    let state = session.state();
+    let recipe = session.recipe();
+    let player = session.player();
+
+    let gauges = [
+        (
+            "Progress",
+            state.progress as i32,
+            recipe.difficulty,
+            Color::Green,
+            progress_area,
+        ),
+        (
+            "Quality",
+            state.quality as i32,
+            recipe.quality_target,
+            Color::Cyan,
+            quality_area,
+        ),
+        (
+            "Durability",
+            state.durability as i32,
+            recipe.durability,
+            Color::Yellow,
+            durability_area,
+        ),
+        ("CP", state.cp as i32, player.cp, Color::Magenta, cp_area),
+    ];
+    for (title, value, max, color, gauge_area) in gauges {
+        let ratio = (value as f64 / max.max(1) as f64).clamp(0.0, 1.0);
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(title))
+                .gauge_style(Style::default().fg(color))
+                .ratio(ratio)
+                .label(format!("{} / {}", value, max)),
+            gauge_area,
+        );
+    }
+
+    frame.render_widget(
+        Paragraph::new(buff_lines(state)).block(Block::bordered().title("Buffs")),
+        buffs_area,
+    );
+}
+
+fn buff_lines(state: &CraftingState) -> Vec<Line<'static>> {
+    [
+        ("Inner Quiet", state.inner_quiet_stacks),
+        ("Veneration", state.veneration_stacks),
+        ("Innovation", state.innovation_stacks),
+        ("Great Strides", state.great_strides_stacks),
+        ("Muscle Memory", state.muscle_memory_stacks),
+        ("Manipulation", state.manipulation_stacks),
+        ("Waste Not", state.waste_not_stacks),
+        ("Final Appraisal", state.final_appraisal_stacks),
+        ("Observe", state.observe_stacks),
+        ("Touch combo", state.touch_combo_stage),
+    ]
+    .into_iter()
+    .filter(|(_, stacks)| *stacks > 0)
+    .map(|(name, stacks)| Line::from(format!("{:<16} {}", name, stacks)))
+    .collect_vec()
+}
+
+fn action_list_item(
+    preview: &ActionPreview,
+    current: &CraftingState,
+    finished: bool,
+) -> ListItem<'static> {
+    if finished {
+        return ListItem::new(preview.action).dim();
+    }
+
+    let text = format!(
+        "{:<20} {:>+5}p {:>+5}q {:>+4}dur {:>+4}cp",
+        preview.action,
+        preview.state.progress as i32 - current.progress as i32,
+        preview.state.quality as i32 - current.quality as i32,
+        preview.state.durability - current.durability,
+        preview.state.cp - current.cp,
+    );
+
+    match preview.issue {
+        Some(issue) if issue.is_fatal() => ListItem::new(format!("{} {:?}", text, issue)).red(),
+        Some(issue) => ListItem::new(format!("{} {:?}", text, issue)).yellow(),
+        None => ListItem::new(text).fg(status_color(preview.status)),
+    }
+}
+
+fn status_color(status: CraftStatus) -> Color {
+    match status {
+        CraftStatus::Success => Color::Cyan,
+        CraftStatus::Failure => Color::Red,
+        CraftStatus::Incomplete => Color::Green,
+    }
+}
+
+struct Args {
+    job_name: String,
+    recipe: String,
+    food: Option<String>,
+    potion: Option<String>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut pargs = pico_args::Arguments::from_env();
+
+    if pargs.contains(["-h", "--help"]) {
+        print!(
+            r"
+USAGE: crafting-session --job WVR --recipe <recipe>
+
+Interactively build up a crafting rotation one action at a time.
+The final sequence is printed as a macro on exit.
+
+FLAGS:
+    -j, --job           references a job listed in jobs.toml
+
+    -r, --recipe        item name for a recipe to simulate
+
+    -f, --food          (optional, assumes HQ) one of:
+                            tsai_tou
+                            jhinga_biryani
+
+    -p, --potion        (optional, assumes HQ) one of:
+                            cunning_draught
+
+    -h, --help          (optional) show this message
+    "
+        );
+        return Err(eyre!(""));
+    }
+
+    let args = Args {
+        job_name: pargs.value_from_str(["-j", "--job"])?,
+        recipe: pargs.value_from_str(["-r", "--recipe"])?,
+        food: pargs.opt_value_from_str(["-f", "--food"])?,
+        potion: pargs.opt_value_from_str(["-p", "--potion"])?,
+    };
+
+    let remaining = pargs.finish();
+    if !remaining.is_empty() {
+        return Err(eyre!("Unrecognised arguments: {:?}", remaining));
+    }
+
+    Ok(args)
+}
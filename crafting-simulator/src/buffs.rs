@@ -1,5 +1,5 @@
 use crate::model::PlayerStats;
-use ff14_data::model::Food;
+use ff14_data::model::{BaseParam, Food};
 
 pub fn apply_buff_hq(player: &PlayerStats, buff: &Food) -> PlayerStats {
     let mut craftsmanship = player.craftsmanship;
@@ -7,11 +7,14 @@ pub fn apply_buff_hq(player: &PlayerStats, buff: &Food) -> PlayerStats {
     let mut cp = player.cp;
 
     for bonus in &buff.bonuses {
-        match bonus.name.as_str() {
-            "CP" => cp += bonus.max_hq as u16,
-            "Control" => control += bonus.max_hq as u16,
-            "Craftsmanship" => craftsmanship += bonus.max_hq as u16,
-            _ => panic!("Unknown bonus type: {}", bonus.name),
+        // some foods and medicines lower a stat, which mustn't wrap around
+        let add = |stat: u16| stat.saturating_add_signed(bonus.amount(stat, true));
+        match bonus.param {
+            BaseParam::CP => cp = add(cp),
+            BaseParam::Control => control = add(control),
+            BaseParam::Craftsmanship => craftsmanship = add(craftsmanship),
+            // eg vitality on most foods: doesn't affect crafting
+            _ => {}
         };
     }
 
@@ -27,6 +30,7 @@ pub fn apply_buff_hq(player: &PlayerStats, buff: &Food) -> PlayerStats {
 mod tests {
     use super::*;
     use crate::presets::Presets as p;
+    use ff14_data::model::{FoodBonus, ItemId};
 
     #[test]
     fn when_player_stats_high_enough_buff_amount_reaches_cap() {
@@ -38,4 +42,27 @@ mod tests {
             PlayerStats::level_90(9000, 9000 + 90, 9000 + 86)
         )
     }
+
+    #[test]
+    fn negative_bonuses_lower_stats_without_wrapping() {
+        let player = PlayerStats::level_90(4000, 3500, 20);
+        let bonus = |param, value, max| FoodBonus::new(param, false, 0, 0, value, max);
+        let buff = Food::new(
+            ItemId::new(1),
+            "Questionable Stew".to_string(),
+            false,
+            vec![
+                bonus(BaseParam::CP, -50, 0),
+                bonus(BaseParam::Control, -10, 0),
+                FoodBonus::new(BaseParam::Craftsmanship, true, 0, 0, 5, 40),
+                // vitality
+                bonus(BaseParam::Other(3), 100, 0),
+            ],
+        );
+
+        assert_eq!(
+            apply_buff_hq(&player, &buff),
+            PlayerStats::level_90(4000 + 40, 3500 - 10, 0)
+        )
+    }
 }
@@ -1,8 +1,15 @@
 mod actions;
+#[cfg(feature = "cli")]
 pub mod arg_utils;
+#[cfg(feature = "ff14-data")]
 pub mod buffs;
+#[cfg(feature = "cli")]
 pub mod config;
+#[cfg(feature = "cli")]
 pub mod generator;
 pub mod model;
 pub mod presets;
+pub mod session;
 pub mod simulator;
+#[cfg(feature = "wasm")]
+pub mod wasm;
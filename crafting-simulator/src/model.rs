@@ -1,8 +1,10 @@
 use derive_more::Constructor;
+#[cfg(feature = "ff14-data")]
 use ff14_data::model::Recipe;
 
 /// A recipe struct with all the non-essential details (eg ingredients) stripped out.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "wasm", derive(serde::Deserialize))]
 pub struct SimulatorRecipe {
     pub rlvl: u16,
     pub progress_divider: u8,
@@ -16,6 +18,7 @@ pub struct SimulatorRecipe {
     pub required_control: u16,
 }
 
+#[cfg(feature = "ff14-data")]
 impl SimulatorRecipe {
     pub fn from_recipe(recipe: &Recipe) -> SimulatorRecipe {
         SimulatorRecipe {
@@ -54,6 +57,7 @@ impl PlayerStats {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub struct CraftingState {
     pub durability: i16,
     pub progress: u16,
@@ -169,6 +173,7 @@ impl<T: InfallibleStep> CraftingStep for T {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub enum CraftStatus {
     Success,
     Failure,
@@ -176,6 +181,7 @@ pub enum CraftStatus {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub enum CraftingIssueType {
     InsufficientStats,
     OutOfCP,
@@ -199,12 +205,14 @@ impl CraftingIssueType {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Constructor)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub struct CraftingIssue {
     pub issue_type: CraftingIssueType,
     pub step_index: u8,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "wasm", derive(serde::Serialize))]
 pub struct CraftingReport {
     /// The list of steps actually run. Differs from the input list of steps
     /// since some steps might fail to trigger or cause the craft to end
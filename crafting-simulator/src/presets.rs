@@ -1,9 +1,12 @@
 use crate::model::*;
+#[cfg(feature = "ff14-data")]
 use ff14_data::{food::FoodLookup, model::Food};
+#[cfg(feature = "ff14-data")]
 use lazy_static::lazy_static;
 
+#[cfg(feature = "ff14-data")]
 lazy_static! {
-    static ref FOODS: FoodLookup = FoodLookup::get_food_lookup().unwrap();
+    static ref FOODS: FoodLookup = FoodLookup::from_embedded().unwrap();
 }
 
 pub struct Presets;
@@ -90,14 +93,17 @@ impl Presets {
         }
     }
 
+    #[cfg(feature = "ff14-data")]
     pub fn tsai_tou_vounou() -> &'static Food {
         FOODS.by_name("Tsai tou Vounou").unwrap()
     }
 
+    #[cfg(feature = "ff14-data")]
     pub fn jhinga_biryani() -> &'static Food {
         FOODS.by_name("Jhinga Biryani").unwrap()
     }
 
+    #[cfg(feature = "ff14-data")]
     pub fn cunning_draught() -> &'static Food {
         FOODS.by_name("Cunning Craftsman's Draught").unwrap()
     }
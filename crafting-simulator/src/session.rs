@@ -0,0 +1,166 @@
+use crate::{model::*, simulator::Simulator};
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+
+/// An in-progress craft that gets built up one action at a time.
+///
+/// Every change re-runs the whole sequence through `Simulator::run_steps`, so the
+/// results always match what `check-recipe` would report for the exported macro.
+pub struct CraftingSession {
+    player: PlayerStats,
+    recipe: SimulatorRecipe,
+    steps: Vec<&'static str>,
+    report: CraftingReport,
+}
+
+/// What would happen if we added a particular action to the current sequence
+#[derive(Debug, Clone)]
+pub struct ActionPreview {
+    pub action: &'static str,
+    /// the first new issue the action would cause, if any
+    pub issue: Option<CraftingIssueType>,
+    pub state: CraftingState,
+    pub status: CraftStatus,
+}
+
+impl CraftingSession {
+    pub fn new(player: PlayerStats, recipe: SimulatorRecipe) -> CraftingSession {
+        let report = Simulator::run_steps(player, &recipe, &[]);
+        CraftingSession {
+            player,
+            recipe,
+            steps: vec![],
+            report,
+        }
+    }
+
+    pub fn recipe(&self) -> &SimulatorRecipe {
+        &self.recipe
+    }
+
+    pub fn player(&self) -> &PlayerStats {
+        &self.player
+    }
+
+    pub fn steps(&self) -> &[&'static str] {
+        &self.steps
+    }
+
+    pub fn report(&self) -> &CraftingReport {
+        &self.report
+    }
+
+    pub fn state(&self) -> &CraftingState {
+        &self.report.final_state
+    }
+
+    /// once the craft has succeeded or failed, any further steps would be ignored
+    pub fn is_finished(&self) -> bool {
+        self.report.status != CraftStatus::Incomplete
+    }
+
+    pub fn apply(&mut self, action: &'static str) -> Result<()> {
+        if self.is_finished() {
+            return Err(eyre!(
+                "Craft is already finished ({:?})",
+                self.report.status
+            ));
+        }
+
+        self.steps.push(action);
+        self.report = Simulator::run_steps(self.player, &self.recipe, &self.steps);
+        Ok(())
+    }
+
+    /// returns the step that was removed, if there was one
+    pub fn undo(&mut self) -> Option<&'static str> {
+        let removed = self.steps.pop();
+        self.report = Simulator::run_steps(self.player, &self.recipe, &self.steps);
+        removed
+    }
+
+    pub fn preview(&self, action: &'static str) -> ActionPreview {
+        let steps = self.steps.iter().copied().chain([action]).collect_vec();
+        let report = Simulator::run_steps(self.player, &self.recipe, &steps);
+
+        ActionPreview {
+            action,
+            issue: report
+                .issues
+                .get(self.report.issues.len())
+                .map(|i| i.issue_type),
+            state: report.final_state,
+            status: report.status,
+        }
+    }
+
+    /// previews every known action against the current state
+    pub fn preview_all(&self) -> Vec<ActionPreview> {
+        Simulator::action_names()
+            .into_iter()
+            .map(|a| self.preview(a))
+            .collect_vec()
+    }
+
+    pub fn to_macro(&self) -> String {
+        self.steps
+            .iter()
+            .map(|s| format!("/ac \"{}\"", s))
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::Presets as p;
+
+    #[test]
+    fn undo_returns_to_previous_state() {
+        let mut session =
+            CraftingSession::new(p::baseline_player(), p::baseline_recipe(1000, 70, 1000));
+        let initial = *session.state();
+
+        session.apply("Basic Synthesis").unwrap();
+        assert_eq!(120, session.state().progress);
+
+        assert_eq!(Some("Basic Synthesis"), session.undo());
+        assert_eq!(initial, *session.state());
+        assert_eq!(None, session.undo());
+    }
+
+    #[test]
+    fn preview_reports_issues_without_changing_the_session() {
+        let session =
+            CraftingSession::new(p::baseline_player(), p::baseline_recipe(1000, 70, 1000));
+
+        let preview = session.preview("Byregot's Blessing");
+
+        assert_eq!(Some(CraftingIssueType::LackingInnerQuiet), preview.issue);
+        assert!(session.steps().is_empty());
+    }
+
+    #[test]
+    fn preview_reports_running_out_of_durability() {
+        let mut session =
+            CraftingSession::new(p::baseline_player(), p::baseline_recipe(1000, 20, 1000));
+        session.apply("Basic Synthesis").unwrap();
+
+        let preview = session.preview("Groundwork");
+
+        assert_eq!(Some(CraftingIssueType::DurabilityFailed), preview.issue);
+        assert_eq!(CraftStatus::Failure, preview.status);
+    }
+
+    #[test]
+    fn no_more_steps_once_the_craft_is_finished() {
+        let mut session =
+            CraftingSession::new(p::baseline_player(), p::baseline_recipe(120, 70, 1000));
+
+        session.apply("Basic Synthesis").unwrap();
+
+        assert!(session.is_finished());
+        assert!(session.apply("Basic Touch").is_err());
+        assert_eq!("/ac \"Basic Synthesis\"", session.to_macro());
+    }
+}
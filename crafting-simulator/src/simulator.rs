@@ -1,6 +1,7 @@
 use crate::{actions::Actions, model::*};
 use color_eyre::eyre::{eyre, Result};
 
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::{Regex, RegexBuilder};
 use std::{collections::HashMap, ops::ControlFlow};
@@ -11,24 +12,34 @@ impl Simulator {
     pub fn run_steps(
         player: PlayerStats,
         recipe: &SimulatorRecipe,
-        steps: &[&'static str],
+        steps: &[&str],
     ) -> CraftingReport {
+        Self::try_run_steps(player, recipe, steps).expect("steps should be valid")
+    }
+
+    /// Like `run_steps`, but returns an error instead of panicking if any of
+    /// the steps can't be parsed (eg when they've come from user input)
+    pub fn try_run_steps(
+        player: PlayerStats,
+        recipe: &SimulatorRecipe,
+        steps: &[&str],
+    ) -> Result<CraftingReport> {
         let initial_state = CraftingState::initial(&player, recipe);
 
         // before simulating anything, check required stats
         if player.craftsmanship < recipe.required_craftsmanship
             || player.control < recipe.required_control
         {
-            return CraftingReport {
+            return Ok(CraftingReport {
                 step_log: vec![],
                 final_state: initial_state,
                 issues: vec![CraftingIssue::new(CraftingIssueType::InsufficientStats, 0)],
                 status: CraftStatus::Failure,
-            };
+            });
         }
 
         let actions = Actions::make_action_lookup();
-        let steps = parse_steps(steps, &actions).expect("steps should be valid");
+        let steps = parse_steps(steps, &actions)?;
         let fold_result = steps.into_iter().try_fold(
             (
                 Vec::<&'static str>::new(),
@@ -126,12 +137,20 @@ impl Simulator {
             (_, _) => CraftStatus::Incomplete,
         };
 
-        CraftingReport {
+        Ok(CraftingReport {
             step_log,
             final_state,
             issues,
             status,
-        }
+        })
+    }
+
+    /// The names of all the actions that `run_steps` knows how to simulate
+    pub fn action_names() -> Vec<&'static str> {
+        Actions::make_action_lookup()
+            .into_keys()
+            .sorted()
+            .collect_vec()
     }
 }
 
@@ -165,8 +184,8 @@ lazy_static! {
 }
 
 fn parse_steps<'a>(
-    steps: &[&'static str],
-    actions: &'a HashMap<&str, Box<dyn CraftingStep>>,
+    steps: &[&str],
+    actions: &'a HashMap<&'static str, Box<dyn CraftingStep>>,
 ) -> Result<Vec<(&'static str, &'a Box<dyn CraftingStep>)>> {
     let mut result = vec![];
     for &step in steps {
@@ -181,10 +200,12 @@ fn parse_steps<'a>(
 
         if let Some(captures) = MACRO_AC_LINE.captures(step) {
             let step_name = captures.name("name").unwrap().as_str();
-            let action = actions
-                .get(step_name)
+            // we log the name from the action lookup rather than the input, so that
+            // callers don't need to keep the input steps around
+            let (&name, action) = actions
+                .get_key_value(step_name)
                 .ok_or_else(|| eyre!("Couldn't find action named: <{}>", step_name))?;
-            result.push((step_name, action));
+            result.push((name, action));
             continue;
         } else {
             return Err(eyre!("Failed to parse step: {}", step));
@@ -411,6 +432,17 @@ mod tests {
         assert_eq!("Manipulation", steps[3].0);
     }
 
+    #[test]
+    fn try_run_steps_reports_unknown_actions() {
+        let result = s::try_run_steps(
+            p::baseline_player(),
+            &p::baseline_recipe(1000, 70, 1000),
+            &["Basic Synthesis", "Hasty Touch"],
+        );
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn craft_fails_to_start_if_insufficient_stats() {
         let report = s::run_steps(
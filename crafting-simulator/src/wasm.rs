@@ -0,0 +1,56 @@
+//! A small JSON-in, JSON-out API so that the simulator can run in the browser.
+//!
+//! There's no embedded recipe data here (to keep the wasm binary small), so
+//! callers need to provide the full recipe stats themselves.
+
+use crate::{
+    model::{PlayerStats, SimulatorRecipe},
+    simulator::Simulator,
+};
+use itertools::Itertools;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Deserialize)]
+struct PlayerStatsJson {
+    craftsmanship: u16,
+    control: u16,
+    cp: u16,
+    /// defaults to the internal level of a level 90 crafter, same as jobs.toml
+    player_lvl: Option<u16>,
+}
+
+impl From<PlayerStatsJson> for PlayerStats {
+    fn from(value: PlayerStatsJson) -> Self {
+        let stats = PlayerStats::level_90(value.craftsmanship, value.control, value.cp);
+        PlayerStats {
+            player_lvl: value.player_lvl.unwrap_or(stats.player_lvl),
+            ..stats
+        }
+    }
+}
+
+/// Runs a list of steps (either action names or macro lines like `/ac "Groundwork" <wait.3>`)
+/// and returns the resulting `CraftingReport` as JSON.
+///
+/// - `stats_json`: `{ "craftsmanship": 4000, "control": 3800, "cp": 600 }`
+/// - `recipe_json`: the fields of `SimulatorRecipe`
+/// - `steps_json`: an array of strings
+#[wasm_bindgen]
+pub fn simulate(stats_json: &str, recipe_json: &str, steps_json: &str) -> Result<String, JsError> {
+    let stats: PlayerStatsJson = serde_json::from_str(stats_json)?;
+    let recipe: SimulatorRecipe = serde_json::from_str(recipe_json)?;
+    let steps: Vec<String> = serde_json::from_str(steps_json)?;
+    let steps = steps.iter().map(|s| s.as_str()).collect_vec();
+
+    let report = Simulator::try_run_steps(stats.into(), &recipe, &steps)
+        .map_err(|e| JsError::new(&e.to_string()))?;
+
+    Ok(serde_json::to_string(&report)?)
+}
+
+/// The names of all the actions that `simulate` understands, as a JSON array
+#[wasm_bindgen]
+pub fn action_names() -> Result<String, JsError> {
+    Ok(serde_json::to_string(&Simulator::action_names())?)
+}
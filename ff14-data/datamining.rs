@@ -0,0 +1,1222 @@
+// CSV parsers for the ffxiv-datamining sheets. Shared between build.rs (to build
+// the embedded data) and src/datamining.rs (so tools can read a checkout at runtime).
+// Whoever includes this needs to provide the *Row types, HashMap, Path and
+// a `csv_warning(&str)` function.
+
+/// Everything we embed, straight from the csv folder of a datamining checkout
+pub fn parse_all(csv_dir: &Path) -> Result<EmbeddedData, Box<dyn std::error::Error>> {
+    let items = parse_items(csv_dir)?;
+    let recipes = parse_recipes(csv_dir)?;
+    let recipe_levels = parse_recipe_levels(csv_dir)?;
+    let materia = parse_materia(csv_dir)?;
+    let materia_join_rates = parse_materia_join_rates(csv_dir, "MateriaJoinRate")?;
+    let materia_join_rates_gather_craft =
+        parse_materia_join_rates(csv_dir, "MateriaJoinRateGatherCraft")?;
+    let gil_shops = parse_gil_shops(csv_dir)?;
+    let place_names = parse_place_names(csv_dir)?;
+    let territory_names = parse_territory_place_names(csv_dir, &place_names)?;
+    let gil_shop_npcs = parse_gil_shop_npcs(csv_dir, &gil_shops, &territory_names)?;
+    let special_shops = parse_special_shops(csv_dir)?;
+    let gathering_items = parse_gathering_items(csv_dir)?;
+    let gathering_points = parse_gathering_points(csv_dir, &place_names)?;
+    let collectable_rewards = parse_collectable_rewards(csv_dir)?;
+    let territories = parse_territories(csv_dir, &place_names)?;
+    let weather_rates = parse_weather_rates(csv_dir)?;
+    let weathers = parse_weathers(csv_dir)?;
+    let craft_leves = parse_craft_leves(csv_dir)?;
+    let foods = parse_foods(csv_dir)?;
+    let company_crafts = parse_company_crafts(csv_dir)?;
+
+    Ok(EmbeddedData {
+        items,
+        recipes,
+        recipe_levels,
+        materia,
+        materia_join_rates,
+        materia_join_rates_gather_craft,
+        gil_shops,
+        gil_shop_npcs,
+        special_shops,
+        gathering_items,
+        gathering_points,
+        collectable_rewards,
+        territories,
+        weather_rates,
+        weathers,
+        craft_leves,
+        foods,
+        company_crafts,
+    })
+}
+
+pub fn parse_items(csv_dir: &Path) -> Result<Vec<ItemRow>, Box<dyn std::error::Error>> {
+    let csv_path = csv_dir.join("Item.csv");
+    let (headers, records) = parse_csv_file(&csv_path)?;
+
+    let names_ja = parse_localized_names(csv_dir, "Item", "ja")?;
+    let names_de = parse_localized_names(csv_dir, "Item", "de")?;
+    let names_fr = parse_localized_names(csv_dir, "Item", "fr")?;
+
+    let mut items = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        if id == 0 {
+            continue;
+        }
+
+        items.push(ItemRow {
+            id,
+            name: get_field(&record, &headers, "Name")?.to_string(),
+            singular: get_field(&record, &headers, "Singular")?.to_string(),
+            plural: get_field(&record, &headers, "Plural")?.to_string(),
+            name_ja: names_ja.get(&id).cloned().unwrap_or_default(),
+            name_de: names_de.get(&id).cloned().unwrap_or_default(),
+            name_fr: names_fr.get(&id).cloned().unwrap_or_default(),
+            ilvl: get_field(&record, &headers, "Level{Item}")?.parse().unwrap_or(0),
+            can_be_hq: get_field(&record, &headers, "CanBeHq")? == "True",
+            equip_slot: get_field(&record, &headers, "EquipSlotCategory")?.parse().unwrap_or(0),
+            is_collectable: get_field(&record, &headers, "IsCollectable")? == "True",
+            stack_size: get_field(&record, &headers, "StackSize")?
+                .parse()
+                .unwrap_or(0),
+            price_mid: get_field(&record, &headers, "Price{Mid}")?
+                .parse()
+                .unwrap_or(0),
+            price_low: get_field(&record, &headers, "Price{Low}")?
+                .parse()
+                .unwrap_or(0),
+            is_untradable: get_field(&record, &headers, "IsUntradable")? == "True",
+            item_search_category: get_field(&record, &headers, "ItemSearchCategory")?
+                .parse()
+                .unwrap_or(0),
+            item_ui_category: get_field(&record, &headers, "ItemUICategory")?
+                .parse()
+                .unwrap_or(0),
+            rarity: get_field(&record, &headers, "Rarity")?.parse().unwrap_or(0),
+            equip_level: get_field(&record, &headers, "Level{Equip}")?
+                .parse()
+                .unwrap_or(0),
+            class_job_category: get_field(&record, &headers, "ClassJobCategory")?
+                .parse()
+                .unwrap_or(0),
+        });
+    }
+
+    Ok(items)
+}
+
+// The other client languages live in separate copies of each sheet, either as
+// csv/<lang>/Item.csv or csv/Item.<lang>.csv depending on how they were exported.
+// They're optional: without them we just fall back to the english names.
+fn parse_localized_names(
+    csv_dir: &Path,
+    sheet: &str,
+    lang: &str,
+) -> Result<HashMap<i32, String>, Box<dyn std::error::Error>> {
+    let candidates = [
+        csv_dir.join(lang).join(format!("{}.csv", sheet)),
+        csv_dir.join(format!("{}.{}.csv", sheet, lang)),
+    ];
+    let Some(csv_path) = candidates.iter().find(|p| p.exists()) else {
+        csv_warning(&format!(
+            "No '{}' names for {}, falling back to english",
+            lang, sheet
+        ));
+        return Ok(HashMap::new());
+    };
+    let (headers, records) = parse_csv_file(csv_path)?;
+
+    let mut names = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let name = get_field(&record, &headers, "Name")?;
+        if !name.is_empty() {
+            names.insert(id, name.to_string());
+        }
+    }
+
+    Ok(names)
+}
+
+pub fn parse_recipes(csv_dir: &Path) -> Result<Vec<RecipeRow>, Box<dyn std::error::Error>> {
+    let csv_path = csv_dir.join("Recipe.csv");
+    let (headers, records) = parse_csv_file(&csv_path)?;
+
+    let mut recipes = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        if id == 0 {
+            continue;
+        }
+
+        let mut item_ingredients = Vec::new();
+        let mut amount_ingredients = Vec::new();
+        for i in 0..8 {
+            let item_id: i32 = get_field(&record, &headers, &format!("Item{{Ingredient}}[{}]", i))?.parse().unwrap_or(0);
+            let amount: u32 = get_field(&record, &headers, &format!("Amount{{Ingredient}}[{}]", i))?.parse().unwrap_or(0);
+            item_ingredients.push(item_id);
+            amount_ingredients.push(amount);
+        }
+
+        recipes.push(RecipeRow {
+            id,
+            number: get_field(&record, &headers, "Number")?.parse().unwrap_or(0),
+            craft_type: get_field(&record, &headers, "CraftType")?.parse().unwrap_or(0),
+            recipe_level_table: get_field(&record, &headers, "RecipeLevelTable")?.parse().unwrap_or(0),
+            item_result: get_field(&record, &headers, "Item{Result}")?.parse().unwrap_or(0),
+            amount_result: get_field(&record, &headers, "Amount{Result}")?.parse().unwrap_or(0),
+            item_ingredients,
+            amount_ingredients,
+            difficulty_factor: get_field(&record, &headers, "DifficultyFactor")?.parse().unwrap_or(0),
+            quality_factor: get_field(&record, &headers, "QualityFactor")?.parse().unwrap_or(0),
+            durability_factor: get_field(&record, &headers, "DurabilityFactor")?.parse().unwrap_or(0),
+            required_craftsmanship: get_field(&record, &headers, "RequiredCraftsmanship")?.parse().unwrap_or(0),
+            required_control: get_field(&record, &headers, "RequiredControl")?.parse().unwrap_or(0),
+        });
+    }
+
+    Ok(recipes)
+}
+
+pub fn parse_recipe_levels(
+    csv_dir: &Path,
+) -> Result<Vec<RecipeLevelRow>, Box<dyn std::error::Error>> {
+    let csv_path = csv_dir.join("RecipeLevelTable.csv");
+    let (headers, records) = parse_csv_file(&csv_path)?;
+
+    let mut levels = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+
+        levels.push(RecipeLevelRow {
+            id,
+            class_job_level: get_field(&record, &headers, "ClassJobLevel")?
+                .parse()
+                .unwrap_or(0),
+            progress_divider: get_field(&record, &headers, "ProgressDivider")?
+                .parse()
+                .unwrap_or(0),
+            progress_modifier: get_field(&record, &headers, "ProgressModifier")?
+                .parse()
+                .unwrap_or(0),
+            quality_divider: get_field(&record, &headers, "QualityDivider")?
+                .parse()
+                .unwrap_or(0),
+            quality_modifier: get_field(&record, &headers, "QualityModifier")?
+                .parse()
+                .unwrap_or(0),
+            difficulty: get_field(&record, &headers, "Difficulty")?
+                .parse()
+                .unwrap_or(0),
+            durability: get_field(&record, &headers, "Durability")?
+                .parse()
+                .unwrap_or(0),
+            quality: get_field(&record, &headers, "Quality")?
+                .parse()
+                .unwrap_or(0),
+            stars: get_field(&record, &headers, "Stars")?.parse().unwrap_or(0),
+        });
+    }
+
+    Ok(levels)
+}
+
+pub fn parse_materia(csv_dir: &Path) -> Result<Vec<MateriaRow>, Box<dyn std::error::Error>> {
+    let csv_path = csv_dir.join("Materia.csv");
+    let (headers, records) = parse_csv_file(&csv_path)?;
+
+    let mut materia = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        if id == 0 {
+            continue;
+        }
+
+        let mut item_ids = Vec::new();
+        let mut values = Vec::new();
+        for i in 0..12 {
+            let item_id: i32 = get_field(&record, &headers, &format!("Item[{}]", i))?.parse().unwrap_or(0);
+            let value: i16 = get_field(&record, &headers, &format!("Value[{}]", i))?.parse().unwrap_or(0);
+            item_ids.push(item_id);
+            values.push(value);
+        }
+        let base_param: u16 = get_field(&record, &headers, "BaseParam")?
+            .parse()
+            .unwrap_or(0);
+
+        materia.push(MateriaRow {
+            id,
+            item_ids,
+            values,
+            base_param,
+        });
+    }
+
+    Ok(materia)
+}
+
+/// `sheet` is MateriaJoinRate or MateriaJoinRateGatherCraft, which share a layout
+pub fn parse_materia_join_rates(
+    csv_dir: &Path,
+    sheet: &str,
+) -> Result<Vec<MateriaJoinRateRow>, Box<dyn std::error::Error>> {
+    let csv_path = csv_dir.join(format!("{}.csv", sheet));
+    let (headers, records) = parse_csv_file(&csv_path)?;
+
+    let mut rates = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+
+        let mut nq = Vec::new();
+        let mut hq = Vec::new();
+        for i in 0..4 {
+            nq.push(
+                get_field(&record, &headers, &format!("NQOverall[{}]", i))?
+                    .parse()
+                    .unwrap_or(0),
+            );
+            hq.push(
+                get_field(&record, &headers, &format!("HQOverall[{}]", i))?
+                    .parse()
+                    .unwrap_or(0),
+            );
+        }
+
+        rates.push(MateriaJoinRateRow { id, nq, hq });
+    }
+
+    Ok(rates)
+}
+
+pub fn parse_gil_shops(csv_dir: &Path) -> Result<Vec<GilShopRow>, Box<dyn std::error::Error>> {
+    // GilShopItem has several rows per shop, with ids like "262144.3"
+    let (headers, records) = parse_csv_file(&csv_dir.join("GilShopItem.csv"))?;
+    let mut items_by_shop = HashMap::<i32, Vec<i32>>::new();
+    for record in records {
+        let shop_id = parse_row_id(get_field(&record, &headers, "#")?)?;
+        let item_id: i32 = get_field(&record, &headers, "Item")?.parse().unwrap_or(0);
+        if item_id != 0 {
+            items_by_shop.entry(shop_id).or_default().push(item_id);
+        }
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("GilShop.csv"))?;
+    let mut shops = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let Some(item_ids) = items_by_shop.remove(&id) else {
+            continue;
+        };
+
+        shops.push(GilShopRow {
+            id,
+            name: get_field(&record, &headers, "Name")?.to_string(),
+            item_ids,
+        });
+    }
+
+    Ok(shops)
+}
+
+pub fn parse_gil_shop_npcs(
+    csv_dir: &Path,
+    gil_shops: &[GilShopRow],
+    territory_names: &HashMap<i32, String>,
+) -> Result<Vec<GilShopNpcRow>, Box<dyn std::error::Error>> {
+    let shop_ids: std::collections::HashSet<i32> = gil_shops.iter().map(|s| s.id).collect();
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("ENpcResident.csv"))?;
+    let mut npc_names = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        npc_names.insert(id, get_field(&record, &headers, "Singular")?.to_string());
+    }
+
+    // Level places objects in the world. Type 8 means the object is an ENpc
+    let (headers, records) = parse_csv_file(&csv_dir.join("Level.csv"))?;
+    let mut npc_locations = HashMap::new();
+    for record in records {
+        if get_field(&record, &headers, "Type")? != "8" {
+            continue;
+        }
+        let npc_id: i32 = get_field(&record, &headers, "Object")?.parse().unwrap_or(0);
+        let territory: i32 = get_field(&record, &headers, "Territory")?
+            .parse()
+            .unwrap_or(0);
+        if let Some(name) = territory_names.get(&territory) {
+            npc_locations.entry(npc_id).or_insert_with(|| name.clone());
+        }
+    }
+
+    // ENpcData can point at all sorts of sheets, so only keep the ids that are gil shops
+    let (headers, records) = parse_csv_file(&csv_dir.join("ENpcBase.csv"))?;
+    let data_columns = headers
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.starts_with("ENpcData["))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let mut npcs = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let gil_shop_ids = data_columns
+            .iter()
+            .filter_map(|&i| record.get(i)?.parse::<i32>().ok())
+            .filter(|data| shop_ids.contains(data))
+            .collect::<Vec<_>>();
+        if gil_shop_ids.is_empty() {
+            continue;
+        }
+
+        npcs.push(GilShopNpcRow {
+            id,
+            name: npc_names.get(&id).cloned().unwrap_or_default(),
+            location: npc_locations.get(&id).cloned().unwrap_or_default(),
+            gil_shop_ids,
+        });
+    }
+
+    Ok(npcs)
+}
+
+// For shops with UseCurrencyType 16, Item{Cost} is a row of the Currency sheet (the current
+// scrips and tomestones) rather than an item id. CollectablesShopRewardScrip uses the same rows.
+// The sheet is optional: without it those costs are left as they are.
+pub fn parse_scrip_currencies(
+    csv_dir: &Path,
+) -> Result<HashMap<i32, i32>, Box<dyn std::error::Error>> {
+    let csv_path = csv_dir.join("Currency.csv");
+    if !csv_path.exists() {
+        csv_warning("No Currency sheet, so scrip costs won't be resolved to items");
+        return Ok(HashMap::new());
+    }
+    let (headers, records) = parse_csv_file(&csv_path)?;
+
+    let mut scrips = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let item_id: i32 = get_field(&record, &headers, "Item")?.parse().unwrap_or(0);
+        if item_id != 0 {
+            scrips.insert(id, item_id);
+        }
+    }
+
+    Ok(scrips)
+}
+
+/// What a SpecialShop's Item{Cost} refers to depends on the shop's UseCurrencyType.
+/// `tomestones` and `scrips` map the TomestonesItem and Currency rows to item ids.
+pub fn resolve_cost_item(
+    use_currency_type: i32,
+    item_id: i32,
+    tomestones: &HashMap<i32, i32>,
+    scrips: &HashMap<i32, i32>,
+) -> i32 {
+    match use_currency_type {
+        // real items with ids this low are all currencies/crystals anyway
+        16 if item_id < 100 => scrips.get(&item_id).copied().unwrap_or(item_id),
+        2 | 4 if item_id < 100 => tomestones.get(&item_id).copied().unwrap_or(item_id),
+        _ => item_id,
+    }
+}
+
+pub fn parse_special_shops(
+    csv_dir: &Path,
+) -> Result<Vec<SpecialShopRow>, Box<dyn std::error::Error>> {
+    // For shops with UseCurrencyType 2 or 4, Item{Cost} is a Tomestones id instead
+    let (headers, records) = parse_csv_file(&csv_dir.join("TomestonesItem.csv"))?;
+    let mut tomestones = HashMap::new();
+    for record in records {
+        let item_id: i32 = get_field(&record, &headers, "Item")?.parse().unwrap_or(0);
+        let tomestone: i32 = get_field(&record, &headers, "Tomestones")?
+            .parse()
+            .unwrap_or(0);
+        if item_id != 0 && tomestone != 0 {
+            tomestones.insert(tomestone, item_id);
+        }
+    }
+    let scrips = parse_scrip_currencies(csv_dir)?;
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("SpecialShop.csv"))?;
+    let receive_items = indexed_columns(&headers, "Item{Receive}");
+    let receive_counts = indexed_columns(&headers, "Count{Receive}");
+    let cost_items = indexed_columns(&headers, "Item{Cost}");
+    let cost_counts = indexed_columns(&headers, "Count{Cost}");
+
+    let mut shops = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let use_currency_type: i32 = get_field(&record, &headers, "UseCurrencyType")?
+            .parse()
+            .unwrap_or(0);
+        let resolve_cost =
+            |item_id: i32| resolve_cost_item(use_currency_type, item_id, &tomestones, &scrips);
+        let read = |columns: &std::collections::BTreeMap<(usize, usize), usize>, entry: usize| {
+            columns
+                .range((entry, 0)..(entry + 1, 0))
+                .map(|(_, &col)| {
+                    record
+                        .get(col)
+                        .and_then(|v| v.parse::<i64>().ok())
+                        .unwrap_or(0)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let entry_count = receive_items
+            .keys()
+            .map(|(entry, _)| entry + 1)
+            .max()
+            .unwrap_or(0);
+        let mut entries = Vec::new();
+        for entry in 0..entry_count {
+            let (receive_item_ids, receive_counts): (Vec<i32>, Vec<u32>) =
+                read(&receive_items, entry)
+                    .into_iter()
+                    .zip(read(&receive_counts, entry))
+                    .filter(|(item, count)| *item > 0 && *count > 0)
+                    .map(|(item, count)| (item as i32, count as u32))
+                    .unzip();
+            let (cost_item_ids, cost_counts): (Vec<i32>, Vec<u32>) = read(&cost_items, entry)
+                .into_iter()
+                .zip(read(&cost_counts, entry))
+                .filter(|(item, count)| *item > 0 && *count > 0)
+                .map(|(item, count)| (resolve_cost(item as i32), count as u32))
+                .unzip();
+            if receive_item_ids.is_empty() || cost_item_ids.is_empty() {
+                continue;
+            }
+
+            entries.push(SpecialShopEntryRow {
+                receive_item_ids,
+                receive_counts,
+                cost_item_ids,
+                cost_counts,
+            });
+        }
+        if entries.is_empty() {
+            continue;
+        }
+
+        shops.push(SpecialShopRow {
+            id,
+            name: get_field(&record, &headers, "Name")?.to_string(),
+            entries,
+        });
+    }
+
+    Ok(shops)
+}
+
+/// Finds columns like "Item{Cost}[3][1]" and returns (3, 1) -> column index.
+/// Columns with a single index like "Item{Cost}[3]" are treated as (3, 0)
+fn indexed_columns(
+    headers: &[String],
+    prefix: &str,
+) -> std::collections::BTreeMap<(usize, usize), usize> {
+    let mut columns = std::collections::BTreeMap::new();
+    for (col, header) in headers.iter().enumerate() {
+        let Some(rest) = header.strip_prefix(prefix) else {
+            continue;
+        };
+        let indices = rest
+            .split(['[', ']'])
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>();
+        match indices.as_deref() {
+            Ok([entry]) => columns.insert((*entry, 0), col),
+            Ok([entry, slot]) => columns.insert((*entry, *slot), col),
+            _ => None,
+        };
+    }
+    columns
+}
+
+pub fn parse_gathering_items(
+    csv_dir: &Path,
+) -> Result<Vec<GatheringItemRow>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("GatheringItemLevelConvertTable.csv"))?;
+    let mut levels = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let level: u16 = get_field(&record, &headers, "GatheringItemLevel")?
+            .parse()
+            .unwrap_or(0);
+        let stars: u8 = get_field(&record, &headers, "Stars")?.parse().unwrap_or(0);
+        levels.insert(id, (level, stars));
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("GatheringItem.csv"))?;
+    let mut gathering_items = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let item_id: i32 = get_field(&record, &headers, "Item")?.parse().unwrap_or(0);
+        // Item can also point at an EventItem (quest items), which we don't care about
+        if item_id <= 0 {
+            continue;
+        }
+        let level_id: i32 = get_field(&record, &headers, "GatheringItemLevel")?
+            .parse()
+            .unwrap_or(0);
+        let (level, stars) = levels.get(&level_id).copied().unwrap_or((0, 0));
+
+        gathering_items.push(GatheringItemRow {
+            id,
+            item_id,
+            level,
+            stars,
+            is_hidden: get_field(&record, &headers, "IsHidden")? == "True",
+        });
+    }
+
+    Ok(gathering_items)
+}
+
+pub fn parse_gathering_points(
+    csv_dir: &Path,
+    place_names: &HashMap<i32, String>,
+) -> Result<Vec<GatheringPointRow>, Box<dyn std::error::Error>> {
+    // the type/level/items live on GatheringPointBase, which lots of points share
+    let (headers, records) = parse_csv_file(&csv_dir.join("GatheringPointBase.csv"))?;
+    let mut bases = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let gathering_type: u8 = get_field(&record, &headers, "GatheringType")?
+            .parse()
+            .unwrap_or(0);
+        let level: u16 = get_field(&record, &headers, "GatheringLevel")?
+            .parse()
+            .unwrap_or(0);
+        let mut item_ids = Vec::new();
+        for i in 0..8 {
+            let item_id: i32 = get_field(&record, &headers, &format!("Item[{}]", i))?.parse().unwrap_or(0);
+            if item_id > 0 {
+                item_ids.push(item_id);
+            }
+        }
+        let is_limited = get_field(&record, &headers, "IsLimited")? == "True";
+        bases.insert(id, (gathering_type, level, item_ids, is_limited));
+    }
+
+    // times are stored like 1430 for 14:30 ET
+    let to_minutes = |hhmm: u16| (hhmm / 100) * 60 + hhmm % 100;
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("GatheringRarePopTimeTable.csv"))?;
+    let mut pop_times = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut windows = Vec::new();
+        for i in 0..3 {
+            let start: u16 = get_field(&record, &headers, &format!("StartTime[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let duration: u16 = get_field(&record, &headers, &format!("Duration{{m}}[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            // Note: some nodes spawn at midnight, so only the duration tells us if the slot is used
+            if duration > 0 {
+                windows.push((to_minutes(start), duration));
+            }
+        }
+        pop_times.insert(id, windows);
+    }
+
+    // GatheringPointTransient shares ids with GatheringPoint
+    let (headers, records) = parse_csv_file(&csv_dir.join("GatheringPointTransient.csv"))?;
+    let mut spawns = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let ephemeral_start: u16 = get_field(&record, &headers, "EphemeralStartTime")?
+            .parse()
+            .unwrap_or(u16::MAX);
+        let ephemeral_end: u16 = get_field(&record, &headers, "EphemeralEndTime")?
+            .parse()
+            .unwrap_or(u16::MAX);
+        let pop_time: i32 = get_field(&record, &headers, "GatheringRarePopTimeTable")?
+            .parse()
+            .unwrap_or(0);
+
+        // unused times are 65535
+        if ephemeral_start != u16::MAX
+            && ephemeral_end != u16::MAX
+            && ephemeral_start != ephemeral_end
+        {
+            let start = to_minutes(ephemeral_start);
+            let duration = (to_minutes(ephemeral_end) + 24 * 60 - start) % (24 * 60);
+            spawns.insert(id, (vec![(start, duration)], true));
+        } else if let Some(windows) = pop_times.get(&pop_time).filter(|w| !w.is_empty()) {
+            spawns.insert(id, (windows.clone(), false));
+        }
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("GatheringPoint.csv"))?;
+    let mut points = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let base_id: i32 = get_field(&record, &headers, "GatheringPointBase")?
+            .parse()
+            .unwrap_or(0);
+        let Some((gathering_type, level, gathering_item_ids, is_limited)) = bases.get(&base_id)
+        else {
+            continue;
+        };
+        if gathering_item_ids.is_empty() {
+            continue;
+        }
+        let territory_type: i32 = get_field(&record, &headers, "TerritoryType")?
+            .parse()
+            .unwrap_or(0);
+        let place_name: i32 = get_field(&record, &headers, "PlaceName")?
+            .parse()
+            .unwrap_or(0);
+        let (windows, is_ephemeral) = spawns.get(&id).cloned().unwrap_or_default();
+        let (spawn_starts, spawn_durations) = windows.into_iter().unzip();
+
+        points.push(GatheringPointRow {
+            id,
+            gathering_type: *gathering_type,
+            level: *level,
+            gathering_item_ids: gathering_item_ids.clone(),
+            territory_type,
+            place_name: place_names.get(&place_name).cloned().unwrap_or_default(),
+            is_limited: *is_limited,
+            spawn_starts,
+            spawn_durations,
+            is_ephemeral,
+        });
+    }
+
+    Ok(points)
+}
+
+pub fn parse_collectable_rewards(
+    csv_dir: &Path,
+) -> Result<Vec<CollectableRewardRow>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("CollectablesShopRefine.csv"))?;
+    let mut refines = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let collectability = [
+            "LowCollectability",
+            "MidCollectability",
+            "HighCollectability",
+        ]
+        .iter()
+        .map(|field| Ok(get_field(&record, &headers, field)?.parse().unwrap_or(0)))
+        .collect::<Result<Vec<u16>, Box<dyn std::error::Error>>>()?;
+        refines.insert(id, collectability);
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("CollectablesShopRewardScrip.csv"))?;
+    let mut scrip_rewards = HashMap::new();
+    // Currency is a row of the Currency sheet, like the scrip SpecialShops' costs
+    let scrips = parse_scrip_currencies(csv_dir)?;
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let currency: i32 = get_field(&record, &headers, "Currency")?
+            .parse()
+            .unwrap_or(0);
+        let Some(&currency_item_id) = scrips.get(&currency) else {
+            continue;
+        };
+        let rewards = ["LowReward", "MidReward", "HighReward"]
+            .iter()
+            .map(|field| Ok(get_field(&record, &headers, field)?.parse().unwrap_or(0)))
+            .collect::<Result<Vec<u32>, Box<dyn std::error::Error>>>()?;
+        scrip_rewards.insert(id, (currency_item_id, rewards));
+    }
+
+    // CollectablesShopItem has several rows per group, with ids like "3.12"
+    let (headers, records) = parse_csv_file(&csv_dir.join("CollectablesShopItem.csv"))?;
+    let mut rewards = Vec::new();
+    let mut seen_items = std::collections::HashSet::new();
+    for record in records {
+        let item_id: i32 = get_field(&record, &headers, "Item")?.parse().unwrap_or(0);
+        let refine_id: i32 = get_field(&record, &headers, "CollectablesShopRefine")?
+            .parse()
+            .unwrap_or(0);
+        let reward_id: i32 = get_field(&record, &headers, "CollectablesShopRewardScrip")?
+            .parse()
+            .unwrap_or(0);
+        let (Some(collectability), Some((currency_item_id, scrips))) =
+            (refines.get(&refine_id), scrip_rewards.get(&reward_id))
+        else {
+            continue;
+        };
+        if item_id <= 0 || !seen_items.insert(item_id) {
+            continue;
+        }
+
+        rewards.push(CollectableRewardRow {
+            item_id,
+            currency_item_id: *currency_item_id,
+            collectability: collectability.clone(),
+            rewards: scrips.clone(),
+        });
+    }
+
+    Ok(rewards)
+}
+
+/// TerritoryTypes that have weather. Lots of these share a name (eg instanced copies of a zone)
+pub fn parse_territories(
+    csv_dir: &Path,
+    place_names: &HashMap<i32, String>,
+) -> Result<Vec<TerritoryRow>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("TerritoryType.csv"))?;
+    let mut territories = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let weather_rate: i32 = get_field(&record, &headers, "WeatherRate")?
+            .parse()
+            .unwrap_or(0);
+        let place_name: i32 = get_field(&record, &headers, "PlaceName")?
+            .parse()
+            .unwrap_or(0);
+        let (Some(name), true) = (place_names.get(&place_name), weather_rate != 0) else {
+            continue;
+        };
+
+        territories.push(TerritoryRow {
+            id,
+            name: name.clone(),
+            weather_rate,
+        });
+    }
+
+    Ok(territories)
+}
+
+pub fn parse_weather_rates(
+    csv_dir: &Path,
+) -> Result<Vec<WeatherRateRow>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("WeatherRate.csv"))?;
+    let mut weather_rates = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut weather_ids = Vec::new();
+        let mut rates = Vec::new();
+        for i in 0..8 {
+            let weather: i32 = get_field(&record, &headers, &format!("Weather[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let rate: u8 = get_field(&record, &headers, &format!("Rate[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            if rate > 0 {
+                weather_ids.push(weather);
+                rates.push(rate);
+            }
+        }
+
+        weather_rates.push(WeatherRateRow {
+            id,
+            weather_ids,
+            rates,
+        });
+    }
+
+    Ok(weather_rates)
+}
+
+pub fn parse_weathers(csv_dir: &Path) -> Result<Vec<WeatherRow>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("Weather.csv"))?;
+    let mut weathers = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let name = get_field(&record, &headers, "Name")?;
+        if !name.is_empty() {
+            weathers.push(WeatherRow {
+                id,
+                name: name.to_string(),
+            });
+        }
+    }
+
+    Ok(weathers)
+}
+
+// ItemAction types whose Data[1] is an ItemFood row
+const FOOD_ACTION_TYPE: u16 = 844;
+const MEDICINE_ACTION_TYPES: [u16; 2] = [845, 846];
+
+pub fn parse_foods(csv_dir: &Path) -> Result<Vec<FoodRow>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("ItemFood.csv"))?;
+    let mut item_foods = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut bonuses = Vec::new();
+        for i in 0..3 {
+            let field = |name: &str| get_field(&record, &headers, &format!("{}[{}]", name, i));
+            let base_param: u16 = field("BaseParam")?.parse().unwrap_or(0);
+            if base_param == 0 {
+                continue;
+            }
+            bonuses.push((
+                base_param,
+                field("IsRelative")? == "True",
+                field("Value")?.parse::<i16>().unwrap_or(0),
+                field("Max")?.parse::<i16>().unwrap_or(0),
+                field("Value{HQ}")?.parse::<i16>().unwrap_or(0),
+                field("Max{HQ}")?.parse::<i16>().unwrap_or(0),
+            ));
+        }
+        if !bonuses.is_empty() {
+            item_foods.insert(id, bonuses);
+        }
+    }
+
+    // ItemAction ID -> (ItemFood ID, is medicine)
+    let (headers, records) = parse_csv_file(&csv_dir.join("ItemAction.csv"))?;
+    let mut actions = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let action_type: u16 = get_field(&record, &headers, "Type")?.parse().unwrap_or(0);
+        if action_type != FOOD_ACTION_TYPE && !MEDICINE_ACTION_TYPES.contains(&action_type) {
+            continue;
+        }
+        let item_food: i32 = get_field(&record, &headers, "Data[1]")?
+            .parse()
+            .unwrap_or(0);
+        actions.insert(id, (item_food, action_type != FOOD_ACTION_TYPE));
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("Item.csv"))?;
+    let mut foods = Vec::new();
+    for record in records {
+        let item_id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let action: i32 = get_field(&record, &headers, "ItemAction")?
+            .parse()
+            .unwrap_or(0);
+        let Some((item_food, is_medicine)) = actions.get(&action) else {
+            continue;
+        };
+        let Some(bonuses) = item_foods.get(item_food) else {
+            continue;
+        };
+
+        foods.push(FoodRow {
+            item_id,
+            is_medicine: *is_medicine,
+            base_params: bonuses.iter().map(|b| b.0).collect(),
+            is_relative: bonuses.iter().map(|b| b.1).collect(),
+            values: bonuses.iter().map(|b| b.2).collect(),
+            maxes: bonuses.iter().map(|b| b.3).collect(),
+            values_hq: bonuses.iter().map(|b| b.4).collect(),
+            maxes_hq: bonuses.iter().map(|b| b.5).collect(),
+        });
+    }
+
+    Ok(foods)
+}
+
+// ClassJobCategory has a True/False column for each job, named after its abbreviation.
+// These are in CraftType order.
+const CRAFTING_JOB_ABBREVIATIONS: [&str; 8] =
+    ["CRP", "BSM", "ARM", "GSM", "LTW", "WVR", "ALC", "CUL"];
+
+pub fn parse_craft_leves(csv_dir: &Path) -> Result<Vec<CraftLeveRow>, Box<dyn std::error::Error>> {
+    // ClassJobCategory ID -> craft type, for categories with exactly one crafting job
+    let (headers, records) = parse_csv_file(&csv_dir.join("ClassJobCategory.csv"))?;
+    let mut category_jobs = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut jobs = Vec::new();
+        for (craft_type, abbreviation) in CRAFTING_JOB_ABBREVIATIONS.iter().enumerate() {
+            if get_field(&record, &headers, abbreviation)? == "True" {
+                jobs.push(craft_type as u32);
+            }
+        }
+        if let [job] = jobs[..] {
+            category_jobs.insert(id, job);
+        }
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("LeveRewardItemGroup.csv"))?;
+    let mut reward_groups = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut rewards = Vec::new();
+        for i in 0..9 {
+            let item_id: i32 = get_field(&record, &headers, &format!("Item[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let count: u32 = get_field(&record, &headers, &format!("Count[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let is_hq = get_field(&record, &headers, &format!("IsHQ[{}]", i))? == "True";
+            if item_id > 0 && count > 0 {
+                rewards.push((item_id, count, is_hq));
+            }
+        }
+        reward_groups.insert(id, rewards);
+    }
+
+    // each LeveRewardItem picks one of up to 8 groups, with some probability
+    let (headers, records) = parse_csv_file(&csv_dir.join("LeveRewardItem.csv"))?;
+    let mut reward_items = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut rewards = Vec::new();
+        for i in 0..8 {
+            let group: i32 = get_field(&record, &headers, &format!("LeveRewardItemGroup[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let probability: u8 = get_field(&record, &headers, &format!("Probability<%>[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            if probability == 0 {
+                continue;
+            }
+            for &(item_id, count, is_hq) in reward_groups.get(&group).into_iter().flatten() {
+                rewards.push((item_id, count, is_hq, probability));
+            }
+        }
+        reward_items.insert(id, rewards);
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("CraftLeve.csv"))?;
+    let mut turn_ins = HashMap::new();
+    for record in records {
+        let leve: i32 = get_field(&record, &headers, "Leve")?.parse().unwrap_or(0);
+        let repeats: u8 = get_field(&record, &headers, "Repeats")?
+            .parse()
+            .unwrap_or(0);
+        let mut items = Vec::new();
+        for i in 0..4 {
+            let item_id: i32 = get_field(&record, &headers, &format!("Item[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let count: u32 = get_field(&record, &headers, &format!("ItemCount[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            if item_id > 0 && count > 0 {
+                items.push((item_id, count));
+            }
+        }
+        if leve > 0 && !items.is_empty() {
+            turn_ins.insert(leve, (repeats, items));
+        }
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("Leve.csv"))?;
+    let mut leves = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let Some((repeats, items)) = turn_ins.remove(&id) else {
+            continue;
+        };
+        let category: i32 = get_field(&record, &headers, "ClassJobCategory")?
+            .parse()
+            .unwrap_or(0);
+        let Some(&craft_type) = category_jobs.get(&category) else {
+            continue;
+        };
+        let reward_item: i32 = get_field(&record, &headers, "LeveRewardItem")?
+            .parse()
+            .unwrap_or(0);
+        let rewards = reward_items.get(&reward_item).cloned().unwrap_or_default();
+
+        leves.push(CraftLeveRow {
+            id,
+            name: get_field(&record, &headers, "Name")?.to_string(),
+            craft_type,
+            class_job_level: get_field(&record, &headers, "ClassJobLevel")?
+                .parse()
+                .unwrap_or(0),
+            exp_reward: get_field(&record, &headers, "ExpReward")?
+                .parse()
+                .unwrap_or(0),
+            gil_reward: get_field(&record, &headers, "GilReward")?
+                .parse()
+                .unwrap_or(0),
+            repeats,
+            item_ids: items.iter().map(|(id, _)| *id).collect(),
+            item_counts: items.iter().map(|(_, count)| *count).collect(),
+            reward_item_ids: rewards.iter().map(|r| r.0).collect(),
+            reward_counts: rewards.iter().map(|r| r.1).collect(),
+            reward_hq: rewards.iter().map(|r| r.2).collect(),
+            reward_probabilities: rewards.iter().map(|r| r.3).collect(),
+        });
+    }
+
+    Ok(leves)
+}
+
+pub fn parse_company_crafts(
+    csv_dir: &Path,
+) -> Result<Vec<CompanyCraftRow>, Box<dyn std::error::Error>> {
+    // CompanyCraftSupplyItem ID -> Item ID
+    let (headers, records) = parse_csv_file(&csv_dir.join("CompanyCraftSupplyItem.csv"))?;
+    let mut supply_items = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let item_id: i32 = get_field(&record, &headers, "Item")?.parse().unwrap_or(0);
+        supply_items.insert(id, item_id);
+    }
+
+    // CompanyCraftProcess ID -> (item, amount) for each supply item, ie one phase
+    let (headers, records) = parse_csv_file(&csv_dir.join("CompanyCraftProcess.csv"))?;
+    let mut processes = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut items = Vec::new();
+        for i in 0..12 {
+            let supply_item: i32 = get_field(&record, &headers, &format!("SupplyItem[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let set_quantity: u32 = get_field(&record, &headers, &format!("SetQuantity[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let sets_required: u32 = get_field(&record, &headers, &format!("SetsRequired[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            let item_id = supply_items.get(&supply_item).copied().unwrap_or(0);
+            if item_id > 0 && set_quantity * sets_required > 0 {
+                items.push((item_id, set_quantity * sets_required));
+            }
+        }
+        processes.insert(id, items);
+    }
+
+    // CompanyCraftPart ID -> its phases, in order
+    let (headers, records) = parse_csv_file(&csv_dir.join("CompanyCraftPart.csv"))?;
+    let mut parts = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let mut phases = Vec::new();
+        for i in 0..3 {
+            let process: i32 =
+                get_field(&record, &headers, &format!("CompanyCraftProcess[{}]", i))?
+                    .parse()
+                    .unwrap_or(0);
+            if let Some(items) = processes.get(&process).filter(|items| !items.is_empty()) {
+                phases.push(items.clone());
+            }
+        }
+        parts.insert(id, phases);
+    }
+
+    let (headers, records) = parse_csv_file(&csv_dir.join("CompanyCraftSequence.csv"))?;
+    let mut crafts = Vec::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let result_item_id: i32 = get_field(&record, &headers, "ResultItem")?
+            .parse()
+            .unwrap_or(0);
+        if result_item_id == 0 {
+            continue;
+        }
+
+        let mut phases = Vec::new();
+        for i in 0..8 {
+            let part: i32 = get_field(&record, &headers, &format!("CompanyCraftPart[{}]", i))?
+                .parse()
+                .unwrap_or(0);
+            for items in parts.get(&part).into_iter().flatten() {
+                phases.push(CompanyCraftPhaseRow {
+                    part: i as u8,
+                    item_ids: items.iter().map(|&(id, _)| id).collect(),
+                    amounts: items.iter().map(|&(_, amount)| amount).collect(),
+                });
+            }
+        }
+
+        crafts.push(CompanyCraftRow {
+            id,
+            result_item_id,
+            phases,
+        });
+    }
+
+    Ok(crafts)
+}
+
+/// PlaceName ID -> name
+pub fn parse_place_names(
+    csv_dir: &Path,
+) -> Result<HashMap<i32, String>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("PlaceName.csv"))?;
+    let mut place_names = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let name = get_field(&record, &headers, "Name")?;
+        if !name.is_empty() {
+            place_names.insert(id, name.to_string());
+        }
+    }
+
+    Ok(place_names)
+}
+
+/// TerritoryType ID -> place name, eg 129 -> "Limsa Lominsa Lower Decks"
+pub fn parse_territory_place_names(
+    csv_dir: &Path,
+    place_names: &HashMap<i32, String>,
+) -> Result<HashMap<i32, String>, Box<dyn std::error::Error>> {
+    let (headers, records) = parse_csv_file(&csv_dir.join("TerritoryType.csv"))?;
+    let mut territory_names = HashMap::new();
+    for record in records {
+        let id: i32 = get_field(&record, &headers, "#")?.parse()?;
+        let place_name: i32 = get_field(&record, &headers, "PlaceName")?
+            .parse()
+            .unwrap_or(0);
+        if let Some(name) = place_names.get(&place_name) {
+            territory_names.insert(id, name.clone());
+        }
+    }
+
+    Ok(territory_names)
+}
+
+/// Sheets with several rows per key use ids like "262144.3". We only want the key part.
+fn parse_row_id(id: &str) -> Result<i32, Box<dyn std::error::Error>> {
+    let key = id.split('.').next().unwrap_or(id);
+    Ok(key.parse()?)
+}
+
+fn get_field<'a>(
+    record: &'a [String],
+    headers: &[String],
+    field_name: &str,
+) -> Result<&'a str, Box<dyn std::error::Error>> {
+    let index = headers
+        .iter()
+        .position(|h| h == field_name)
+        .ok_or_else(|| format!("Field '{}' not found in headers", field_name))?;
+    Ok(record.get(index).map(|s| s.as_str()).unwrap_or(""))
+}
+
+fn parse_csv_file(
+    path: &Path,
+) -> Result<(Vec<String>, Vec<Vec<String>>), Box<dyn std::error::Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(path)?;
+
+    let mut raw_records = reader.records();
+
+    // Skip first row (column indices)
+    raw_records.next();
+
+    // Read second row (field names)
+    let header_record = raw_records
+        .next()
+        .transpose()?
+        .ok_or("Missing header row")?;
+    let headers: Vec<String> = header_record.iter().map(|s| s.to_string()).collect();
+
+    // Skip third row (data types)
+    raw_records.next();
+
+    // Parse data rows
+    let mut records = Vec::new();
+    for record in raw_records {
+        let record = record?;
+        let row: Vec<String> = record.iter().map(|s| s.to_string()).collect();
+        records.push(row);
+    }
+
+    Ok((headers, records))
+}
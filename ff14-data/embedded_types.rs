@@ -6,18 +6,40 @@
 
 use rkyv::{Archive, Deserialize, Serialize};
 
+/// Data files (including the embedded one) start with these 8 bytes, then the schema hash
+/// as a little-endian u64, so we can tell when a file was written by a different version
+pub const DATA_FILE_MAGIC: &[u8; 8] = b"FF14DATA";
+/// 16 bytes keeps the rkyv data after it aligned
+pub const DATA_FILE_HEADER_LEN: usize = 16;
+
 #[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
 pub struct ItemRow {
     pub id: i32,
     pub name: String,
     pub singular: String,
     pub plural: String,
+    /// empty if the localised csvs weren't available at build time
+    pub name_ja: String,
+    pub name_de: String,
+    pub name_fr: String,
     pub ilvl: u32,
     pub can_be_hq: bool,
     pub equip_slot: u32,
+    pub is_collectable: bool,
+    pub stack_size: u32,
+    pub price_mid: u32,
+    pub price_low: u32,
+    pub is_untradable: bool,
+    pub item_search_category: u32,
+    pub item_ui_category: u32,
+    pub rarity: u8,
+    pub equip_level: u16,
+    pub class_job_category: u32,
 }
 
 #[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
 pub struct RecipeRow {
     pub id: i32,
     pub number: u32,
@@ -35,8 +57,10 @@ pub struct RecipeRow {
 }
 
 #[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
 pub struct RecipeLevelRow {
     pub id: i32,
+    pub class_job_level: u8,
     pub progress_divider: u8,
     pub progress_modifier: u8,
     pub quality_divider: u8,
@@ -48,16 +72,201 @@ pub struct RecipeLevelRow {
 }
 
 #[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
 pub struct MateriaRow {
     pub id: i32,
     pub item_ids: Vec<i32>,
     pub values: Vec<i16>,
+    pub base_param: u16,
+}
+
+/// Overmeld success chances (in percent) for one grade of materia, by overmeld slot
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct MateriaJoinRateRow {
+    /// 0 for grade I materia
+    pub id: i32,
+    pub nq: Vec<u8>,
+    pub hq: Vec<u8>,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct GilShopRow {
+    pub id: i32,
+    pub name: String,
+    pub item_ids: Vec<i32>,
+}
+
+/// An NPC with at least one gil shop
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct GilShopNpcRow {
+    pub id: i32,
+    pub name: String,
+    /// place name of the zone they stand in, or empty if we couldn't find one
+    pub location: String,
+    pub gil_shop_ids: Vec<i32>,
+}
+
+/// One trade in a SpecialShop. The ids/counts are parallel arrays, with empty slots removed
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct SpecialShopEntryRow {
+    pub receive_item_ids: Vec<i32>,
+    pub receive_counts: Vec<u32>,
+    pub cost_item_ids: Vec<i32>,
+    pub cost_counts: Vec<u32>,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct SpecialShopRow {
+    pub id: i32,
+    pub name: String,
+    pub entries: Vec<SpecialShopEntryRow>,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct GatheringItemRow {
+    pub id: i32,
+    pub item_id: i32,
+    pub level: u16,
+    pub stars: u8,
+    pub is_hidden: bool,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct GatheringPointRow {
+    pub id: i32,
+    pub gathering_type: u8,
+    pub level: u16,
+    pub gathering_item_ids: Vec<i32>,
+    pub territory_type: i32,
+    /// the area within the zone, or empty if it doesn't have one
+    pub place_name: String,
+    pub is_limited: bool,
+    /// when timed nodes appear, in Eorzean minutes after midnight
+    pub spawn_starts: Vec<u16>,
+    /// how long they stay up for, in Eorzean minutes
+    pub spawn_durations: Vec<u16>,
+    pub is_ephemeral: bool,
+}
+
+/// What a collectable turns into at the collectables appraiser
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct CollectableRewardRow {
+    pub item_id: i32,
+    pub currency_item_id: i32,
+    /// low, mid and high collectability thresholds
+    pub collectability: Vec<u16>,
+    /// scrips for reaching each of the thresholds
+    pub rewards: Vec<u32>,
+}
+
+/// A zone with weather
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct TerritoryRow {
+    pub id: i32,
+    pub name: String,
+    pub weather_rate: i32,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct WeatherRateRow {
+    pub id: i32,
+    pub weather_ids: Vec<i32>,
+    /// percent chance of each weather, in the same order as weather_ids
+    pub rates: Vec<u8>,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct WeatherRow {
+    pub id: i32,
+    pub name: String,
+}
+
+/// A crafting leve, with its CraftLeve turn-in and LeveRewardItem rewards merged in
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct CraftLeveRow {
+    pub id: i32,
+    pub name: String,
+    pub craft_type: u32,
+    pub class_job_level: u8,
+    pub exp_reward: u32,
+    pub gil_reward: u32,
+    pub repeats: u8,
+    pub item_ids: Vec<i32>,
+    pub item_counts: Vec<u32>,
+    /// possible reward items, as parallel arrays
+    pub reward_item_ids: Vec<i32>,
+    pub reward_counts: Vec<u32>,
+    pub reward_hq: Vec<bool>,
+    pub reward_probabilities: Vec<u8>,
+}
+
+/// An item that gives an ItemFood buff (ie food or medicine)
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct FoodRow {
+    pub item_id: i32,
+    pub is_medicine: bool,
+    /// one entry per bonus, as parallel arrays
+    pub base_params: Vec<u16>,
+    pub is_relative: Vec<bool>,
+    pub values: Vec<i16>,
+    pub maxes: Vec<i16>,
+    pub values_hq: Vec<i16>,
+    pub maxes_hq: Vec<i16>,
+}
+
+/// One phase of a workshop project. The ids/amounts are parallel arrays, already
+/// multiplied out from the sets the phase needs
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct CompanyCraftPhaseRow {
+    /// which part of the project this phase builds, counting from 0
+    pub part: u8,
+    pub item_ids: Vec<i32>,
+    pub amounts: Vec<u32>,
+}
+
+/// A workshop project, from CompanyCraftSequence
+#[derive(Archive, Deserialize, Serialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct CompanyCraftRow {
+    pub id: i32,
+    pub result_item_id: i32,
+    pub phases: Vec<CompanyCraftPhaseRow>,
 }
 
-#[derive(Archive, Deserialize, Serialize, Debug)]
+#[derive(Archive, Deserialize, Serialize, Debug, Default)]
+#[archive(check_bytes)]
 pub struct EmbeddedData {
     pub items: Vec<ItemRow>,
     pub recipes: Vec<RecipeRow>,
     pub recipe_levels: Vec<RecipeLevelRow>,
     pub materia: Vec<MateriaRow>,
+    pub materia_join_rates: Vec<MateriaJoinRateRow>,
+    /// crafting and gathering gear has its own rates
+    pub materia_join_rates_gather_craft: Vec<MateriaJoinRateRow>,
+    pub gil_shops: Vec<GilShopRow>,
+    pub gil_shop_npcs: Vec<GilShopNpcRow>,
+    pub special_shops: Vec<SpecialShopRow>,
+    pub gathering_items: Vec<GatheringItemRow>,
+    pub gathering_points: Vec<GatheringPointRow>,
+    pub collectable_rewards: Vec<CollectableRewardRow>,
+    pub territories: Vec<TerritoryRow>,
+    pub weather_rates: Vec<WeatherRateRow>,
+    pub weathers: Vec<WeatherRow>,
+    pub craft_leves: Vec<CraftLeveRow>,
+    pub foods: Vec<FoodRow>,
+    pub company_crafts: Vec<CompanyCraftRow>,
 }
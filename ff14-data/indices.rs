@@ -0,0 +1,203 @@
+// Index maps over EmbeddedData, so we can find rows without scanning the whole Vec.
+// Shared between build.rs (which bakes them into phf maps) and src/embedded_data.rs
+// (which builds them at runtime when the data gets loaded from disk instead).
+// Whoever includes this needs to provide EmbeddedData, ArchivedEmbeddedData and search_key.
+
+/// The languages we index item names in. English keeps the unsuffixed phf map names,
+/// eg ITEM_NAME_TO_INDEX vs ITEM_NAME_JA_TO_INDEX
+pub const INDEXED_LANGUAGES: [&str; 4] = ["", "_JA", "_DE", "_FR"];
+
+/// The columns DataIndices needs. Implemented for both EmbeddedData and its archived form,
+/// so data loaded at runtime can be indexed without deserializing all of it first.
+/// Everything comes back in row order.
+pub trait IndexSource {
+    /// item id, and its name in each of INDEXED_LANGUAGES
+    fn item_names(&self) -> impl Iterator<Item = (i32, [&str; 4])>;
+    /// recipe id, result item, and the ingredient item ids and amounts
+    fn recipe_ingredients(&self) -> impl Iterator<Item = (i32, i32, &[i32], &[u32])>;
+    fn recipe_level_ids(&self) -> impl Iterator<Item = i32>;
+    fn gil_shop_item_ids(&self) -> impl Iterator<Item = &[i32]>;
+    fn npc_gil_shop_ids(&self) -> impl Iterator<Item = &[i32]>;
+    fn gathering_item_item_ids(&self) -> impl Iterator<Item = i32>;
+    fn gathering_point_item_ids(&self) -> impl Iterator<Item = &[i32]>;
+    fn collectable_reward_item_ids(&self) -> impl Iterator<Item = i32>;
+}
+
+// the archived rows have the same field names, and their strings/vecs deref the same way
+macro_rules! impl_index_source {
+    ($data:ty) => {
+        impl IndexSource for $data {
+            fn item_names(&self) -> impl Iterator<Item = (i32, [&str; 4])> {
+                self.items.iter().map(|i| {
+                    (
+                        i.id,
+                        [
+                            i.name.as_str(),
+                            i.name_ja.as_str(),
+                            i.name_de.as_str(),
+                            i.name_fr.as_str(),
+                        ],
+                    )
+                })
+            }
+
+            fn recipe_ingredients(&self) -> impl Iterator<Item = (i32, i32, &[i32], &[u32])> {
+                self.recipes.iter().map(|r| {
+                    (
+                        r.id,
+                        r.item_result,
+                        &r.item_ingredients[..],
+                        &r.amount_ingredients[..],
+                    )
+                })
+            }
+
+            fn recipe_level_ids(&self) -> impl Iterator<Item = i32> {
+                self.recipe_levels.iter().map(|r| r.id)
+            }
+
+            fn gil_shop_item_ids(&self) -> impl Iterator<Item = &[i32]> {
+                self.gil_shops.iter().map(|s| &s.item_ids[..])
+            }
+
+            fn npc_gil_shop_ids(&self) -> impl Iterator<Item = &[i32]> {
+                self.gil_shop_npcs.iter().map(|n| &n.gil_shop_ids[..])
+            }
+
+            fn gathering_item_item_ids(&self) -> impl Iterator<Item = i32> {
+                self.gathering_items.iter().map(|gi| gi.item_id)
+            }
+
+            fn gathering_point_item_ids(&self) -> impl Iterator<Item = &[i32]> {
+                self.gathering_points
+                    .iter()
+                    .map(|p| &p.gathering_item_ids[..])
+            }
+
+            fn collectable_reward_item_ids(&self) -> impl Iterator<Item = i32> {
+                self.collectable_rewards.iter().map(|r| r.item_id)
+            }
+        }
+    };
+}
+
+impl_index_source!(EmbeddedData);
+impl_index_source!(ArchivedEmbeddedData);
+
+#[derive(Default)]
+pub struct DataIndices {
+    pub item_by_id: std::collections::BTreeMap<i32, usize>,
+    /// one map per language in INDEXED_LANGUAGES
+    pub item_by_name: [std::collections::BTreeMap<String, usize>; 4],
+    pub items_by_search_key: [std::collections::BTreeMap<String, Vec<usize>>; 4],
+    pub recipe_by_id: std::collections::BTreeMap<i32, usize>,
+    pub recipes_by_result: std::collections::BTreeMap<i32, Vec<usize>>,
+    pub recipes_by_ingredient: std::collections::BTreeMap<i32, Vec<usize>>,
+    pub recipe_level_by_id: std::collections::BTreeMap<i32, usize>,
+    pub gil_shops_by_item: std::collections::BTreeMap<i32, Vec<usize>>,
+    pub npcs_by_gil_shop: std::collections::BTreeMap<i32, Vec<usize>>,
+    pub gathering_items_by_item: std::collections::BTreeMap<i32, Vec<usize>>,
+    pub points_by_gathering_item: std::collections::BTreeMap<i32, Vec<usize>>,
+    pub collectable_reward_by_item: std::collections::BTreeMap<i32, usize>,
+}
+
+impl DataIndices {
+    pub fn build(data: &impl IndexSource) -> DataIndices {
+        let mut indices = DataIndices::default();
+
+        for (idx, (id, names)) in data.item_names().enumerate() {
+            indices.item_by_id.entry(id).or_insert(idx);
+
+            for (language, name) in names.into_iter().enumerate() {
+                if name.is_empty() {
+                    continue;
+                }
+                // Note: some items share names, so we keep the first one for exact matches ...
+                indices.item_by_name[language]
+                    .entry(name.to_string())
+                    .or_insert(idx);
+
+                // ... but every item that shares a search key
+                let key = search_key(name);
+                if !key.is_empty() {
+                    indices.items_by_search_key[language]
+                        .entry(key)
+                        .or_default()
+                        .push(idx);
+                }
+            }
+        }
+
+        // Note: multiple recipes can produce the same item (eg different jobs, or collectable versions)
+        for (idx, (id, result, ingredients, amounts)) in data.recipe_ingredients().enumerate() {
+            indices.recipe_by_id.entry(id).or_insert(idx);
+            indices
+                .recipes_by_result
+                .entry(result)
+                .or_default()
+                .push(idx);
+
+            // unused ingredient slots are item 0
+            for (&item_id, &amount) in ingredients.iter().zip(amounts) {
+                if item_id > 0 && amount > 0 {
+                    let recipes = indices.recipes_by_ingredient.entry(item_id).or_default();
+                    if recipes.last() != Some(&idx) {
+                        recipes.push(idx);
+                    }
+                }
+            }
+        }
+
+        for (idx, id) in data.recipe_level_ids().enumerate() {
+            indices.recipe_level_by_id.entry(id).or_insert(idx);
+        }
+
+        for (idx, item_ids) in data.gil_shop_item_ids().enumerate() {
+            for item_id in item_ids {
+                let shops = indices.gil_shops_by_item.entry(*item_id).or_default();
+                // some shops list the same item more than once
+                if shops.last() != Some(&idx) {
+                    shops.push(idx);
+                }
+            }
+        }
+
+        for (idx, shop_ids) in data.npc_gil_shop_ids().enumerate() {
+            for shop_id in shop_ids {
+                indices
+                    .npcs_by_gil_shop
+                    .entry(*shop_id)
+                    .or_default()
+                    .push(idx);
+            }
+        }
+
+        // Note: some items have more than one GatheringItem row (eg a hidden version)
+        for (idx, item_id) in data.gathering_item_item_ids().enumerate() {
+            indices
+                .gathering_items_by_item
+                .entry(item_id)
+                .or_default()
+                .push(idx);
+        }
+
+        for (idx, gathering_item_ids) in data.gathering_point_item_ids().enumerate() {
+            for gathering_item_id in gathering_item_ids {
+                indices
+                    .points_by_gathering_item
+                    .entry(*gathering_item_id)
+                    .or_default()
+                    .push(idx);
+            }
+        }
+
+        for (idx, item_id) in data.collectable_reward_item_ids().enumerate() {
+            indices
+                .collectable_reward_by_item
+                .entry(item_id)
+                .or_insert(idx);
+        }
+
+        indices
+    }
+}
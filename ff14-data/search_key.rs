@@ -0,0 +1,20 @@
+// Shared between build.rs (to build the search index) and src/search.rs (to
+// normalise queries), so that both sides always agree on what a key looks like.
+
+/// Folds a name down to something that's easy to compare: lowercase ascii,
+/// no accents or punctuation, single spaces between words.
+/// eg "Gyr Abanian Mineral Water" -> "gyr abanian mineral water",
+///    "Ul'dahn Übersuit" -> "uldahn ubersuit"
+pub fn search_key(name: &str) -> String {
+    let mut key = String::with_capacity(name.len());
+    for c in deunicode::deunicode(name).chars() {
+        if c.is_ascii_alphanumeric() {
+            key.push(c.to_ascii_lowercase());
+        } else if (c.is_whitespace() || c == '-') && !key.is_empty() && !key.ends_with(' ') {
+            key.push(' ');
+        }
+        // anything else (apostrophes, brackets etc) gets dropped
+    }
+    key.truncate(key.trim_end().len());
+    key
+}
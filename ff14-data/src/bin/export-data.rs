@@ -0,0 +1,20 @@
+use color_eyre::eyre::{eyre, Result};
+use ff14_data::embedded_data;
+use std::{env, path::Path};
+
+/// Turns an ffxiv-datamining checkout into a data file, so that binaries built without the
+/// `embedded-data` feature can load it (with FFXIV_DATAMINING_PATH) without parsing the csvs
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let [checkout, out] = &args[..] else {
+        return Err(eyre!(
+            "Usage: export-data <ffxiv-datamining checkout> <output file>"
+        ));
+    };
+
+    embedded_data::write_data_file(Path::new(checkout), Path::new(out))?;
+    println!("Wrote {}", out);
+    Ok(())
+}
@@ -0,0 +1,535 @@
+use color_eyre::eyre::{eyre, Result};
+use ff14_data::{datamining, embedded_data::*};
+use itertools::Itertools;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    path::Path,
+    process::Command,
+};
+
+/// Compares two ffxiv-datamining snapshots and prints what got added, removed or changed.
+///
+/// Each snapshot is either a checkout (or its csv directory), or `<checkout>@<git ref>`
+/// to read the sheets from a tag or commit without checking it out.
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = env::args().skip(1).collect_vec();
+    let json = args.iter().any(|a| a == "--json");
+    let snapshots = args.iter().filter(|a| !a.starts_with("--")).collect_vec();
+    let [old, new] = snapshots[..] else {
+        return Err(eyre!(
+            "Usage: patch-diff <old> <new> [--json]\n\
+            where each snapshot is a ffxiv-datamining checkout, optionally with @<git ref>"
+        ));
+    };
+
+    let old = Snapshot::load(old)?;
+    let new = Snapshot::load(new)?;
+
+    // label rows with the newest name we have for each item
+    let names: HashMap<i32, &str> = old
+        .items
+        .iter()
+        .chain(new.items.iter())
+        .map(|i| (i.id, i.name.as_str()))
+        .collect();
+
+    let sheets = [
+        ("Items", diff_rows(&old.items, &new.items, &names)),
+        ("Recipes", diff_rows(&old.recipes, &new.recipes, &names)),
+        (
+            "Recipe levels",
+            diff_rows(&old.recipe_levels, &new.recipe_levels, &names),
+        ),
+        ("Materia", diff_rows(&old.materia, &new.materia, &names)),
+        (
+            "Gil shops",
+            diff_rows(&old.gil_shops, &new.gil_shops, &names),
+        ),
+        (
+            "Special shops",
+            diff_rows(&old.special_shops, &new.special_shops, &names),
+        ),
+    ];
+
+    if json {
+        print_json(&sheets)?;
+    } else {
+        print_text(&sheets);
+    }
+
+    Ok(())
+}
+
+struct Snapshot {
+    items: Vec<ItemRow>,
+    recipes: Vec<RecipeRow>,
+    recipe_levels: Vec<RecipeLevelRow>,
+    materia: Vec<MateriaRow>,
+    gil_shops: Vec<GilShopRow>,
+    special_shops: Vec<SpecialShopRow>,
+}
+
+// the sheets that Snapshot::parse needs, for pulling out of a git ref
+const SHEETS: [&str; 8] = [
+    "Item",
+    "Recipe",
+    "RecipeLevelTable",
+    "Materia",
+    "GilShop",
+    "GilShopItem",
+    "SpecialShop",
+    "TomestonesItem",
+];
+const LANGUAGES: [&str; 3] = ["ja", "de", "fr"];
+// sheets that Snapshot::parse copes without
+const OPTIONAL_SHEETS: [&str; 1] = ["Currency"];
+
+impl Snapshot {
+    fn load(spec: &str) -> Result<Snapshot> {
+        match spec.split_once('@') {
+            Some((repo, git_ref)) => {
+                let dir = env::temp_dir().join(format!(
+                    "patch-diff-{}-{}",
+                    std::process::id(),
+                    git_ref.replace(['/', '\\'], "_")
+                ));
+                let snapshot = extract_sheets(Path::new(repo), git_ref, &dir)
+                    .and_then(|_| Snapshot::parse(&dir));
+                std::fs::remove_dir_all(&dir)?;
+                snapshot
+            }
+            None => Snapshot::parse(&datamining::csv_dir(Path::new(spec))),
+        }
+    }
+
+    fn parse(csv_dir: &Path) -> Result<Snapshot> {
+        // the parsers were written for build.rs, so their errors aren't Send + Sync
+        let parsed = |e: Box<dyn std::error::Error>| eyre!("{} (in {})", e, csv_dir.display());
+        Ok(Snapshot {
+            items: datamining::parse_items(csv_dir).map_err(parsed)?,
+            recipes: datamining::parse_recipes(csv_dir).map_err(parsed)?,
+            recipe_levels: datamining::parse_recipe_levels(csv_dir).map_err(parsed)?,
+            materia: datamining::parse_materia(csv_dir).map_err(parsed)?,
+            gil_shops: datamining::parse_gil_shops(csv_dir).map_err(parsed)?,
+            special_shops: datamining::parse_special_shops(csv_dir).map_err(parsed)?,
+        })
+    }
+}
+
+/// Copies the sheets we need at `git_ref` into a temporary folder with `git show`,
+/// which is much quicker than checking out the whole (large) repo.
+fn extract_sheets(repo: &Path, git_ref: &str, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    let git_show = |path: &str| {
+        Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .arg("show")
+            .arg(format!("{}:csv/{}", git_ref, path))
+            .output()
+    };
+
+    for sheet in SHEETS {
+        let output = git_show(&format!("{}.csv", sheet))?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "Couldn't read {}.csv at {}: {}",
+                sheet,
+                git_ref,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        std::fs::write(dir.join(format!("{}.csv", sheet)), output.stdout)?;
+    }
+
+    for sheet in OPTIONAL_SHEETS {
+        let output = git_show(&format!("{}.csv", sheet))?;
+        if output.status.success() {
+            std::fs::write(dir.join(format!("{}.csv", sheet)), output.stdout)?;
+        }
+    }
+
+    // localized names are optional, and could be in either layout
+    for lang in LANGUAGES {
+        for path in [format!("{}/Item.csv", lang), format!("Item.{}.csv", lang)] {
+            let output = git_show(&path)?;
+            if output.status.success() {
+                let target = dir.join(&path);
+                std::fs::create_dir_all(target.parent().unwrap())?;
+                std::fs::write(target, output.stdout)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A sheet row that we know how to compare between patches
+trait DiffRow {
+    fn id(&self) -> i32;
+    fn label(&self, names: &HashMap<i32, &str>) -> String;
+    /// The values we care about, by (roughly) their column name
+    fn fields(&self, names: &HashMap<i32, &str>) -> BTreeMap<String, String>;
+}
+
+fn item_name(names: &HashMap<i32, &str>, id: i32) -> String {
+    names
+        .get(&id)
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| format!("item {}", id))
+}
+
+fn amounts(names: &HashMap<i32, &str>, ids: &[i32], counts: &[u32]) -> String {
+    ids.iter()
+        .zip(counts)
+        .filter(|(&id, _)| id != 0)
+        .map(|(&id, count)| format!("{}x {}", count, item_name(names, id)))
+        .join(", ")
+}
+
+impl DiffRow for ItemRow {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn label(&self, _: &HashMap<i32, &str>) -> String {
+        self.name.clone()
+    }
+
+    fn fields(&self, _: &HashMap<i32, &str>) -> BTreeMap<String, String> {
+        [
+            ("Name", self.name.clone()),
+            ("Level{Item}", self.ilvl.to_string()),
+            ("Level{Equip}", self.equip_level.to_string()),
+            ("CanBeHq", self.can_be_hq.to_string()),
+            ("IsCollectable", self.is_collectable.to_string()),
+            ("IsUntradable", self.is_untradable.to_string()),
+            ("StackSize", self.stack_size.to_string()),
+            ("Price{Mid}", self.price_mid.to_string()),
+            ("Price{Low}", self.price_low.to_string()),
+            ("ItemSearchCategory", self.item_search_category.to_string()),
+            ("ItemUICategory", self.item_ui_category.to_string()),
+            ("Rarity", self.rarity.to_string()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+}
+
+impl DiffRow for RecipeRow {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn label(&self, names: &HashMap<i32, &str>) -> String {
+        item_name(names, self.item_result)
+    }
+
+    fn fields(&self, names: &HashMap<i32, &str>) -> BTreeMap<String, String> {
+        [
+            ("CraftType", self.craft_type.to_string()),
+            ("RecipeLevelTable", self.recipe_level_table.to_string()),
+            ("Item{Result}", item_name(names, self.item_result)),
+            ("Amount{Result}", self.amount_result.to_string()),
+            (
+                "Ingredients",
+                amounts(names, &self.item_ingredients, &self.amount_ingredients),
+            ),
+            ("DifficultyFactor", self.difficulty_factor.to_string()),
+            ("QualityFactor", self.quality_factor.to_string()),
+            ("DurabilityFactor", self.durability_factor.to_string()),
+            (
+                "RequiredCraftsmanship",
+                self.required_craftsmanship.to_string(),
+            ),
+            ("RequiredControl", self.required_control.to_string()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+}
+
+impl DiffRow for RecipeLevelRow {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn label(&self, _: &HashMap<i32, &str>) -> String {
+        format!(
+            "level {} {}",
+            self.class_job_level,
+            "★".repeat(self.stars.into())
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    fn fields(&self, _: &HashMap<i32, &str>) -> BTreeMap<String, String> {
+        [
+            ("ClassJobLevel", self.class_job_level.to_string()),
+            ("Stars", self.stars.to_string()),
+            ("Difficulty", self.difficulty.to_string()),
+            ("Quality", self.quality.to_string()),
+            ("Durability", self.durability.to_string()),
+            ("ProgressDivider", self.progress_divider.to_string()),
+            ("ProgressModifier", self.progress_modifier.to_string()),
+            ("QualityDivider", self.quality_divider.to_string()),
+            ("QualityModifier", self.quality_modifier.to_string()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+}
+
+impl DiffRow for MateriaRow {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn label(&self, names: &HashMap<i32, &str>) -> String {
+        match self.item_ids.first() {
+            Some(&id) if id != 0 => item_name(names, id),
+            _ => format!("materia {}", self.id),
+        }
+    }
+
+    fn fields(&self, names: &HashMap<i32, &str>) -> BTreeMap<String, String> {
+        // one field per grade, so a new grade shows up as its own change
+        let mut fields: BTreeMap<String, String> = self
+            .item_ids
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .filter(|(_, (&id, _))| id != 0)
+            .map(|(grade, (&id, value))| {
+                (
+                    format!("Item[{}]", grade),
+                    format!("{} (+{})", item_name(names, id), value),
+                )
+            })
+            .collect();
+        fields.insert("BaseParam".to_string(), self.base_param.to_string());
+        fields
+    }
+}
+
+impl DiffRow for GilShopRow {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn label(&self, _: &HashMap<i32, &str>) -> String {
+        self.name.clone()
+    }
+
+    fn fields(&self, names: &HashMap<i32, &str>) -> BTreeMap<String, String> {
+        self.item_ids
+            .iter()
+            .map(|&id| (item_name(names, id), "sold".to_string()))
+            .collect()
+    }
+}
+
+impl DiffRow for SpecialShopRow {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn label(&self, _: &HashMap<i32, &str>) -> String {
+        self.name.clone()
+    }
+
+    fn fields(&self, names: &HashMap<i32, &str>) -> BTreeMap<String, String> {
+        // keyed by what you get, so that price changes show up as changes
+        // rather than as one entry being removed and another added
+        let mut fields = BTreeMap::<String, String>::new();
+        for entry in &self.entries {
+            let receive = amounts(names, &entry.receive_item_ids, &entry.receive_counts);
+            let cost = amounts(names, &entry.cost_item_ids, &entry.cost_counts);
+            fields
+                .entry(receive)
+                .and_modify(|c| *c = format!("{} or {}", c, cost))
+                .or_insert(cost);
+        }
+        fields
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct FieldChange {
+    field: String,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct RowChange {
+    id: i32,
+    label: String,
+    fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SheetDiff {
+    added: Vec<RowChange>,
+    removed: Vec<RowChange>,
+    changed: Vec<RowChange>,
+}
+
+fn diff_rows<T: DiffRow>(old: &[T], new: &[T], names: &HashMap<i32, &str>) -> SheetDiff {
+    let old_rows: BTreeMap<i32, &T> = old.iter().map(|r| (r.id(), r)).collect();
+    let new_rows: BTreeMap<i32, &T> = new.iter().map(|r| (r.id(), r)).collect();
+
+    let mut diff = SheetDiff::default();
+    for id in old_rows.keys().chain(new_rows.keys()).unique().sorted() {
+        let old_row = old_rows.get(id);
+        let new_row = new_rows.get(id);
+        let old_fields = old_row.map(|r| r.fields(names)).unwrap_or_default();
+        let new_fields = new_row.map(|r| r.fields(names)).unwrap_or_default();
+
+        let fields = old_fields
+            .keys()
+            .chain(new_fields.keys())
+            .unique()
+            .sorted()
+            .filter(|f| old_fields.get(*f) != new_fields.get(*f))
+            .map(|f| FieldChange {
+                field: f.clone(),
+                old: old_fields.get(f).cloned(),
+                new: new_fields.get(f).cloned(),
+            })
+            .collect_vec();
+
+        let label = new_row.or(old_row).unwrap().label(names);
+        let change = RowChange {
+            id: *id,
+            label,
+            fields,
+        };
+        match (old_row, new_row) {
+            (None, Some(_)) => diff.added.push(change),
+            (Some(_), None) => diff.removed.push(change),
+            _ if !change.fields.is_empty() => diff.changed.push(change),
+            _ => {}
+        }
+    }
+
+    diff
+}
+
+fn print_text(sheets: &[(&str, SheetDiff)]) {
+    for (name, diff) in sheets {
+        println!(
+            "{}: {} added, {} removed, {} changed",
+            name,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+        for row in &diff.added {
+            println!("  + {} {}", row.id, row.label);
+        }
+        for row in &diff.removed {
+            println!("  - {} {}", row.id, row.label);
+        }
+        for row in &diff.changed {
+            println!("  ~ {} {}", row.id, row.label);
+            for f in &row.fields {
+                println!(
+                    "      {}: {} -> {}",
+                    f.field,
+                    f.old.as_deref().unwrap_or("(none)"),
+                    f.new.as_deref().unwrap_or("(none)")
+                );
+            }
+        }
+        println!();
+    }
+}
+
+fn print_json(sheets: &[(&str, SheetDiff)]) -> Result<()> {
+    let rows = |rows: &[RowChange]| {
+        rows.iter()
+            .map(|r| {
+                json!({
+                    "id": r.id,
+                    "name": r.label,
+                    "fields": r.fields.iter().map(|f| json!({
+                        "field": f.field,
+                        "old": f.old,
+                        "new": f.new,
+                    })).collect_vec(),
+                })
+            })
+            .collect_vec()
+    };
+
+    let output: serde_json::Map<String, serde_json::Value> = sheets
+        .iter()
+        .map(|(name, diff)| {
+            (
+                name.to_string(),
+                json!({
+                    "added": rows(&diff.added),
+                    "removed": rows(&diff.removed),
+                    "changed": rows(&diff.changed),
+                }),
+            )
+        })
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shop(id: i32, item_ids: Vec<i32>) -> GilShopRow {
+        GilShopRow {
+            id,
+            name: format!("shop {}", id),
+            item_ids,
+        }
+    }
+
+    #[test]
+    fn diff_rows_finds_added_removed_and_changed_rows() {
+        let names = HashMap::from([(1, "Iron Ore"), (2, "Honey")]);
+        let old = vec![shop(1, vec![1]), shop(2, vec![1])];
+        let new = vec![shop(2, vec![2]), shop(3, vec![])];
+
+        let diff = diff_rows(&old, &new, &names);
+
+        assert_eq!(diff.added.iter().map(|r| r.id).collect_vec(), vec![3]);
+        assert_eq!(diff.removed.iter().map(|r| r.id).collect_vec(), vec![1]);
+        assert_eq!(
+            diff.changed,
+            vec![RowChange {
+                id: 2,
+                label: "shop 2".to_string(),
+                fields: vec![
+                    FieldChange {
+                        field: "Honey".to_string(),
+                        old: None,
+                        new: Some("sold".to_string())
+                    },
+                    FieldChange {
+                        field: "Iron Ore".to_string(),
+                        old: Some("sold".to_string()),
+                        new: None
+                    },
+                ]
+            }]
+        );
+    }
+}
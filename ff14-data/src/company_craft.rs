@@ -0,0 +1,57 @@
+use crate::model::*;
+use derive_more::Constructor;
+
+/// One phase of a workshop project, eg the second phase of a submarine hull
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct CompanyCraftPhase {
+    /// which part of the project this builds, counting from 0
+    pub part: u8,
+    pub items: Vec<RecipeItem>,
+}
+
+/// Something built in the company workshop, like an airship or submarine part
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct CompanyCraft {
+    pub id: CompanyCraftId,
+    pub result: ItemId,
+    /// every phase of every part, in the order they get built
+    pub phases: Vec<CompanyCraftPhase>,
+}
+
+impl CompanyCraft {
+    /// Everything the project needs across all its phases
+    pub fn total_materials(&self) -> Vec<RecipeItem> {
+        let mut total: Vec<RecipeItem> = vec![];
+        for ri in self.phases.iter().flat_map(|p| &p.items) {
+            match total.iter_mut().find(|t| t.item_id == ri.item_id) {
+                Some(existing) => existing.amount += ri.amount,
+                None => total.push(ri.clone()),
+            }
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ri(item_id: i32, amount: u32) -> RecipeItem {
+        RecipeItem::new(ItemId::new(item_id), amount)
+    }
+
+    #[test]
+    fn total_materials_sums_across_phases() {
+        let craft = CompanyCraft::new(
+            CompanyCraftId::new(1),
+            ItemId::new(100),
+            vec![
+                CompanyCraftPhase::new(0, vec![ri(1, 6)]),
+                CompanyCraftPhase::new(0, vec![ri(2, 10), ri(1, 1)]),
+                CompanyCraftPhase::new(1, vec![ri(3, 4)]),
+            ],
+        );
+
+        assert_eq!(craft.total_materials(), vec![ri(1, 7), ri(2, 10), ri(3, 4)]);
+    }
+}
@@ -0,0 +1,152 @@
+//! Reads sheets straight out of an ffxiv-datamining checkout, using the same
+//! parsers that build.rs uses for the embedded data.
+
+use crate::embedded_data::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+include!(concat!(env!("CARGO_MANIFEST_DIR"), "/datamining.rs"));
+
+/// Accept either a checkout or its csv folder
+pub fn csv_dir(path: &Path) -> std::path::PathBuf {
+    let nested = path.join("csv");
+    if nested.is_dir() {
+        nested
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn csv_warning(message: &str) {
+    eprintln!("warning: {}", message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_shop_costs_resolve_by_currency_type() {
+        let tomestones = HashMap::from([(2, 47)]);
+        let scrips = HashMap::from([(6, 41784), (7, 41785)]);
+        let resolve = |currency_type, item_id| {
+            resolve_cost_item(currency_type, item_id, &tomestones, &scrips)
+        };
+
+        assert_eq!(resolve(16, 7), 41785);
+        assert_eq!(resolve(4, 2), 47);
+        assert_eq!(resolve(2, 2), 47);
+        // plain item ids, even when they happen to look like a currency index
+        assert_eq!(resolve(0, 7), 7);
+        assert_eq!(resolve(16, 45690), 45690);
+        // unknown rows are left alone rather than guessed at
+        assert_eq!(resolve(16, 3), 3);
+    }
+
+    /// Writes a sheet in the datamining layout: column indices, names, then types
+    fn write_sheet(dir: &Path, sheet: &str, columns: &[String], rows: &[Vec<i32>]) {
+        let mut csv = format!(
+            "key,{}\n",
+            (0..columns.len())
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        csv += &format!("#,{}\n", columns.join(","));
+        csv += &format!("int32,{}\n", vec!["int32"; columns.len()].join(","));
+        for row in rows {
+            csv += &row
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            csv += "\n";
+        }
+        std::fs::write(dir.join(format!("{}.csv", sheet)), csv).unwrap();
+    }
+
+    fn indexed(name: &str, count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("{}[{}]", name, i)).collect()
+    }
+
+    #[test]
+    fn company_crafts_join_parts_and_processes_in_order() {
+        let dir =
+            std::env::temp_dir().join(format!("ff14-data-company-crafts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        write_sheet(
+            &dir,
+            "CompanyCraftSupplyItem",
+            &["Item".to_string()],
+            &[vec![1, 5000], vec![2, 5001]],
+        );
+        let process_columns = [
+            indexed("SupplyItem", 12),
+            indexed("SetQuantity", 12),
+            indexed("SetsRequired", 12),
+        ]
+        .concat();
+        let process = |id: i32, slots: &[(i32, i32, i32)]| {
+            let mut row = vec![0; 37];
+            row[0] = id;
+            for (i, &(supply_item, set_quantity, sets_required)) in slots.iter().enumerate() {
+                row[1 + i] = supply_item;
+                row[13 + i] = set_quantity;
+                row[25 + i] = sets_required;
+            }
+            row
+        };
+        write_sheet(
+            &dir,
+            "CompanyCraftProcess",
+            &process_columns,
+            &[
+                process(10, &[(1, 3, 4), (2, 5, 2)]),
+                process(11, &[(2, 2, 3)]),
+            ],
+        );
+        // part 20 builds process 11 before process 10
+        write_sheet(
+            &dir,
+            "CompanyCraftPart",
+            &indexed("CompanyCraftProcess", 3),
+            &[vec![20, 11, 10, 0], vec![21, 10, 0, 0]],
+        );
+        let sequence_columns = [
+            vec!["ResultItem".to_string()],
+            indexed("CompanyCraftPart", 8),
+        ]
+        .concat();
+        write_sheet(
+            &dir,
+            "CompanyCraftSequence",
+            &sequence_columns,
+            &[
+                vec![1, 9000, 21, 20, 0, 0, 0, 0, 0, 0],
+                vec![2, 0, 20, 0, 0, 0, 0, 0, 0, 0],
+            ],
+        );
+
+        let crafts = parse_company_crafts(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let crafts = crafts.unwrap();
+
+        // the sequence without a result is skipped
+        assert_eq!(crafts.len(), 1);
+        assert_eq!(crafts[0].result_item_id, 9000);
+        let phases = crafts[0]
+            .phases
+            .iter()
+            .map(|p| (p.part, p.item_ids.clone(), p.amounts.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            vec![
+                (0, vec![5000, 5001], vec![12, 10]),
+                (1, vec![5001], vec![6]),
+                (1, vec![5000, 5001], vec![12, 10]),
+            ]
+        );
+    }
+}
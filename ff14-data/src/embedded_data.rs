@@ -1,47 +1,211 @@
+use crate::company_craft::{CompanyCraft, CompanyCraftPhase};
+use crate::datamining;
+use crate::leve::{Leve, LeveRewardItem};
 use crate::model::*;
+use crate::search::search_key;
 use color_eyre::eyre::{eyre, Context, Result};
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use rustc_hash::FxHashMap;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 // code generated by build.rs
 // defines the following constants:
-// - EMBEDDED_DATA_BYTES: &[u8] - rkyv-serialized EmbeddedData
-// - ITEM_ID_TO_INDEX, ITEM_NAME_TO_INDEX, RECIPE_ID_TO_INDEX,
-//   RECIPE_RESULT_TO_INDEX: phf lookup maps
+// - EMBEDDED_DATA_BYTES: &[u8] - rkyv-serialized EmbeddedData, after a data file header
+// - DATA_SCHEMA_HASH: u64 - identifies the layout of EmbeddedData, for that header
+// - ITEM_ID_TO_INDEX, RECIPE_ID_TO_INDEX, RECIPE_RESULT_TO_INDICES, RECIPE_INGREDIENT_TO_INDICES,
+//   GIL_SHOP_ITEM_TO_SHOP_INDICES, GIL_SHOP_TO_NPC_INDICES: phf lookup maps
+// - ITEM_NAME_TO_INDEX, ITEM_SEARCH_KEY_TO_INDICES: phf lookup maps for english names,
+//   plus _JA/_DE/_FR versions of each for the other languages
 include!(concat!(env!("OUT_DIR"), "/generated_lookups.rs"));
 
 // types shared between build.rs and here to make sure they line up
 include!(concat!(env!("CARGO_MANIFEST_DIR"), "/embedded_types.rs"));
 
+// the same indices that build.rs turns into phf maps, for data loaded at runtime
+include!(concat!(env!("CARGO_MANIFEST_DIR"), "/indices.rs"));
+
+enum DataSource {
+    Embedded(&'static ArchivedEmbeddedData),
+    Loaded(Box<LoadedData>),
+}
+
+/// Data read from disk with `load_from`, rather than embedded at build time
+struct LoadedData {
+    path: PathBuf,
+    archived: &'static ArchivedEmbeddedData,
+    indices: DataIndices,
+}
+
+static DATA: OnceLock<DataSource> = OnceLock::new();
+
+fn data_source() -> Result<&'static DataSource> {
+    if let Some(data) = DATA.get() {
+        return Ok(data);
+    }
+
+    let data = if cfg!(feature = "embedded-data") {
+        let bytes = strip_header(EMBEDDED_DATA_BYTES).wrap_err("Embedded data is invalid")?;
+        // SAFETY: we skip bytecheck validation here, since the same build wrote this data
+        // and it's a lot of bytes to check on every startup. Files loaded at runtime get
+        // checked in `load_data` instead
+        DataSource::Embedded(unsafe { rkyv::archived_root::<EmbeddedData>(bytes) })
+    } else {
+        // built without any data, so fall back to the same variable build.rs would have used
+        let path = std::env::var_os("FFXIV_DATAMINING_PATH").ok_or_else(|| {
+            eyre!(
+                "ff14-data was built without embedded data: set FFXIV_DATAMINING_PATH to an \
+                ffxiv-datamining checkout or a file from export-data"
+            )
+        })?;
+        let loaded = load_data(Path::new(&path))
+            .wrap_err("Failed to load the data that FFXIV_DATAMINING_PATH points to")?;
+        DataSource::Loaded(Box::new(loaded))
+    };
+    // if another thread got there first, use theirs so everyone sees the same data
+    Ok(DATA.get_or_init(|| data))
+}
+
 // rkyv lets us load bytes directly into memory as rust data structures
-fn get_archived_data() -> &'static ArchivedEmbeddedData {
-    static ARCHIVED: OnceLock<&'static ArchivedEmbeddedData> = OnceLock::new();
-    ARCHIVED.get_or_init(|| unsafe {
-        // SAFETY: we don't do any validation, since we assume that the same code
-        // has been used to both write and read the data, so the layout should match
-        rkyv::archived_root::<EmbeddedData>(EMBEDDED_DATA_BYTES)
+fn get_archived_data() -> Result<&'static ArchivedEmbeddedData> {
+    Ok(match data_source()? {
+        DataSource::Embedded(archived) => archived,
+        DataSource::Loaded(loaded) => loaded.archived,
     })
 }
 
+/// Only used after one of the `read_*` functions has loaded the data, so we don't need to
+/// load it (or report errors) here
+fn loaded_indices() -> Option<&'static DataIndices> {
+    match DATA.get() {
+        Some(DataSource::Loaded(loaded)) => Some(&loaded.indices),
+        _ => None,
+    }
+}
+
+/// Use the data at `path` instead of what was embedded at build time. `path` is either an
+/// ffxiv-datamining checkout (or its csv folder), or a file written by `write_data_file`.
+///
+/// This has to happen before anything reads the data, since the lookups keep indices into it.
+pub fn load_from(path: &Path) -> Result<()> {
+    match DATA.get() {
+        Some(DataSource::Loaded(loaded)) if loaded.path == path => return Ok(()),
+        Some(_) => {
+            return Err(eyre!(
+                "Can't load data from {}: some data has already been read",
+                path.display()
+            ))
+        }
+        None => {}
+    }
+
+    let loaded = load_data(path)?;
+    DATA.set(DataSource::Loaded(Box::new(loaded))).map_err(|_| {
+        eyre!(
+            "Can't load data from {}: some data has already been read",
+            path.display()
+        )
+    })
+}
+
+fn load_data(path: &Path) -> Result<LoadedData> {
+    let bytes = if path.is_file() {
+        let file =
+            std::fs::read(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let mut bytes = rkyv::AlignedVec::new();
+        bytes.extend_from_slice(
+            strip_header(&file).wrap_err_with(|| format!("Can't load {}", path.display()))?,
+        );
+        bytes
+    } else {
+        serialize(&parse_checkout(path)?)?
+    };
+
+    // the header only tells us the file claims to be the right version, so
+    // check the archive itself too before trusting any of its offsets
+    rkyv::check_archived_root::<EmbeddedData>(&bytes)
+        .map_err(|e| eyre!("{} is corrupt: {}", path.display(), e))?;
+
+    let bytes: &'static rkyv::AlignedVec = Box::leak(Box::new(bytes));
+    // SAFETY: these are the same bytes we just validated
+    let archived = unsafe { rkyv::archived_root::<EmbeddedData>(bytes) };
+
+    Ok(LoadedData {
+        path: path.to_path_buf(),
+        archived,
+        indices: DataIndices::build(archived),
+    })
+}
+
+/// Checks that `file` was written with the same schema as this build, and returns the rkyv data after the header
+fn strip_header(file: &[u8]) -> Result<&[u8]> {
+    if file.len() < DATA_FILE_HEADER_LEN || &file[..DATA_FILE_MAGIC.len()] != DATA_FILE_MAGIC {
+        return Err(eyre!("not an ff14-data data file"));
+    }
+    let hash = u64::from_le_bytes(file[DATA_FILE_MAGIC.len()..DATA_FILE_HEADER_LEN].try_into()?);
+    if hash != DATA_SCHEMA_HASH {
+        return Err(eyre!(
+            "data file schema {:#018x} doesn't match this build ({:#018x}). Regenerate it with export-data",
+            hash,
+            DATA_SCHEMA_HASH
+        ));
+    }
+    Ok(&file[DATA_FILE_HEADER_LEN..])
+}
+
+fn parse_checkout(path: &Path) -> Result<EmbeddedData> {
+    let csv_dir = datamining::csv_dir(path);
+    // the parsers are shared with build.rs, so their errors aren't Send + Sync
+    datamining::parse_all(&csv_dir)
+        .map_err(|e| eyre!("Failed to parse {}: {}", csv_dir.display(), e))
+}
+
+fn serialize(data: &EmbeddedData) -> Result<rkyv::AlignedVec> {
+    rkyv::to_bytes::<_, 256>(data).map_err(|e| eyre!("Failed to serialize data: {:?}", e))
+}
+
+/// Parses an ffxiv-datamining checkout into a file that `load_from` can read much faster
+pub fn write_data_file(checkout: &Path, out: &Path) -> Result<()> {
+    let mut bytes = DATA_FILE_MAGIC.to_vec();
+    bytes.extend_from_slice(&DATA_SCHEMA_HASH.to_le_bytes());
+    bytes.extend_from_slice(&serialize(&parse_checkout(checkout)?)?);
+    std::fs::write(out, &bytes).wrap_err_with(|| format!("Failed to write {}", out.display()))
+}
+
 fn item_row_to_item(row: &ArchivedItemRow) -> Result<Item> {
     let item_id = ItemId::new(row.id);
     let equip_slot = EquipSlotCategory::from(row.equip_slot)
         .with_context(|| format!("item {}", item_id))?;
 
-    Ok(Item::new(
-        item_id,
-        row.name.to_string(),
-        row.singular.to_string(),
-        row.plural.to_string(),
-        row.ilvl,
-        row.can_be_hq,
-        equip_slot,
-    ))
+    Ok(Item {
+        id: item_id,
+        name: row.name.to_string(),
+        name_singular: row.singular.to_string(),
+        name_plural: row.plural.to_string(),
+        ilvl: row.ilvl,
+        can_be_hq: row.can_be_hq,
+        equip_slot_category: equip_slot,
+        is_collectable: row.is_collectable,
+        localized_names: LocalizedNames::new(
+            row.name_ja.to_string(),
+            row.name_de.to_string(),
+            row.name_fr.to_string(),
+        ),
+        stack_size: row.stack_size,
+        vendor_price: row.price_mid,
+        vendor_sell_price: row.price_low,
+        is_untradable: row.is_untradable,
+        item_search_category: row.item_search_category,
+        item_ui_category: row.item_ui_category,
+        rarity: row.rarity,
+        equip_level: row.equip_level,
+        class_job_category: row.class_job_category,
+    })
 }
 
 fn recipe_row_to_recipe(row: &ArchivedRecipeRow, rlvl_map: &FxHashMap<RecipeLevelId, RecipeLevel>) -> Result<Recipe> {
     let recipe_id = RecipeId::new(row.id);
+    let job = CraftingJob::from(row.craft_type).with_context(|| format!("recipe {}", recipe_id))?;
 
     let result_id = ItemId::new(row.item_result);
     let result_amount = row.amount_result;
@@ -66,21 +230,22 @@ fn recipe_row_to_recipe(row: &ArchivedRecipeRow, rlvl_map: &FxHashMap<RecipeLeve
     let durability = modify_by_factor(rlvl.base_durability, row.durability_factor);
     let quality_target = modify_by_factor(rlvl.base_quality_target, row.quality_factor);
 
-    Ok(Recipe::new(
-        recipe_id,
+    Ok(Recipe {
+        id: recipe_id,
+        job,
         ingredients,
         result,
         rlvl,
         difficulty,
         durability,
         quality_target,
-        row.required_craftsmanship,
-        row.required_control,
-    ))
+        required_craftsmanship: row.required_craftsmanship,
+        required_control: row.required_control,
+    })
 }
 
 pub fn read_recipes() -> Result<Vec<Recipe>> {
-    let archived = get_archived_data();
+    let archived = get_archived_data()?;
     let rlvls = read_rlvls()?;
     let rlvl_map: FxHashMap<RecipeLevelId, RecipeLevel> =
         rlvls.iter().map(|rl| (rl.rlvl, rl.clone())).collect();
@@ -98,29 +263,30 @@ fn modify_by_factor(base: u16, factor: u16) -> u16 {
 }
 
 pub fn read_rlvls() -> Result<Vec<RecipeLevel>> {
-    let archived = get_archived_data();
+    let archived = get_archived_data()?;
 
     archived
         .recipe_levels
         .iter()
         .map(|row| {
-            Ok(RecipeLevel::new(
-                RecipeLevelId::new(row.id),
-                row.progress_divider,
-                row.progress_modifier,
-                row.quality_divider,
-                row.quality_modifier,
-                row.difficulty,
-                row.durability,
-                row.quality,
-                row.stars,
-            ))
+            Ok(RecipeLevel {
+                rlvl: RecipeLevelId::new(row.id),
+                class_job_level: row.class_job_level,
+                progress_divider: row.progress_divider,
+                progress_modifier: row.progress_modifier,
+                quality_divider: row.quality_divider,
+                quality_modifier: row.quality_modifier,
+                base_difficulty: row.difficulty,
+                base_durability: row.durability,
+                base_quality_target: row.quality,
+                stars: row.stars,
+            })
         })
         .collect()
 }
 
 pub fn read_items() -> Result<Vec<Item>> {
-    get_archived_data()
+    get_archived_data()?
         .items
         .iter()
         .map(item_row_to_item)
@@ -129,20 +295,445 @@ pub fn read_items() -> Result<Vec<Item>> {
 
 // These return indices into the Vec created by read_items()/read_recipes()
 // which has the same ordering as the archived data
+fn index_of(map: &'static std::collections::BTreeMap<i32, usize>, key: i32) -> Option<usize> {
+    map.get(&key).copied()
+}
+
+fn indices_of(
+    map: &'static std::collections::BTreeMap<i32, Vec<usize>>,
+    key: i32,
+) -> &'static [usize] {
+    map.get(&key).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
 pub fn get_item_index_by_id(id: ItemId) -> Option<usize> {
-    ITEM_ID_TO_INDEX.get(&id.into()).copied()
+    match loaded_indices() {
+        Some(indices) => index_of(&indices.item_by_id, id.into()),
+        None => ITEM_ID_TO_INDEX.get(&id.into()).copied(),
+    }
+}
+
+/// index into INDEXED_LANGUAGES
+fn language_index(language: Language) -> usize {
+    match language {
+        Language::English => 0,
+        Language::Japanese => 1,
+        Language::German => 2,
+        Language::French => 3,
+    }
+}
+
+fn item_name_map(language: Language) -> &'static phf::Map<&'static str, usize> {
+    match language {
+        Language::English => &ITEM_NAME_TO_INDEX,
+        Language::Japanese => &ITEM_NAME_JA_TO_INDEX,
+        Language::German => &ITEM_NAME_DE_TO_INDEX,
+        Language::French => &ITEM_NAME_FR_TO_INDEX,
+    }
+}
+
+fn item_search_key_map(language: Language) -> &'static phf::Map<&'static str, &'static [usize]> {
+    match language {
+        Language::English => &ITEM_SEARCH_KEY_TO_INDICES,
+        Language::Japanese => &ITEM_SEARCH_KEY_JA_TO_INDICES,
+        Language::German => &ITEM_SEARCH_KEY_DE_TO_INDICES,
+        Language::French => &ITEM_SEARCH_KEY_FR_TO_INDICES,
+    }
+}
+
+pub fn get_item_index_by_name(name: &str, language: Language) -> Option<usize> {
+    match loaded_indices() {
+        Some(indices) => indices.item_by_name[language_index(language)]
+            .get(name)
+            .copied(),
+        None => item_name_map(language).get(name).copied(),
+    }
+}
+
+/// `key` should already have been through `search::search_key`
+pub fn get_item_indices_by_search_key(key: &str, language: Language) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices.items_by_search_key[language_index(language)]
+            .get(key)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[]),
+        None => item_search_key_map(language)
+            .get(key)
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+pub fn item_search_keys(
+    language: Language,
+) -> impl Iterator<Item = (&'static str, &'static [usize])> {
+    match loaded_indices() {
+        Some(indices) => Either::Left(
+            indices.items_by_search_key[language_index(language)]
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_slice())),
+        ),
+        None => Either::Right(
+            item_search_key_map(language)
+                .entries()
+                .map(|(k, v)| (*k, *v)),
+        ),
+    }
+}
+
+pub fn get_recipe_index_by_id(id: RecipeId) -> Option<usize> {
+    match loaded_indices() {
+        Some(indices) => index_of(&indices.recipe_by_id, id.into()),
+        None => RECIPE_ID_TO_INDEX.get(&id.into()).copied(),
+    }
+}
+
+pub fn get_recipe_indices_by_result_item(item_id: ItemId) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices_of(&indices.recipes_by_result, item_id.into()),
+        None => RECIPE_RESULT_TO_INDICES
+            .get(&item_id.into())
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+pub fn get_recipe_indices_by_ingredient(item_id: ItemId) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices_of(&indices.recipes_by_ingredient, item_id.into()),
+        None => RECIPE_INGREDIENT_TO_INDICES
+            .get(&item_id.into())
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+pub fn get_gil_shop_indices_by_item(item_id: ItemId) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices_of(&indices.gil_shops_by_item, item_id.into()),
+        None => GIL_SHOP_ITEM_TO_SHOP_INDICES
+            .get(&item_id.into())
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+fn get_npc_indices_by_gil_shop(shop_id: i32) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices_of(&indices.npcs_by_gil_shop, shop_id),
+        None => GIL_SHOP_TO_NPC_INDICES
+            .get(&shop_id)
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+pub fn read_gil_shops() -> Result<Vec<GilShop>> {
+    let archived = get_archived_data()?;
+
+    archived
+        .gil_shops
+        .iter()
+        .map(|row| {
+            let vendors = get_npc_indices_by_gil_shop(row.id)
+                .iter()
+                .map(|&i| {
+                    let npc = &archived.gil_shop_npcs[i];
+                    Vendor::new(
+                        ENpcId::new(npc.id),
+                        npc.name.to_string(),
+                        Some(npc.location.to_string()).filter(|l| !l.is_empty()),
+                    )
+                })
+                .collect_vec();
+            let items = row.item_ids.iter().map(|&id| ItemId::new(id)).collect_vec();
+
+            Ok(GilShop::new(
+                GilShopId::new(row.id),
+                row.name.to_string(),
+                items,
+                vendors,
+            ))
+        })
+        .collect()
+}
+
+pub fn read_currency_exchanges() -> Result<Vec<CurrencyExchange>> {
+    let archived = get_archived_data()?;
+
+    let to_items = |ids: &rkyv::vec::ArchivedVec<i32>, counts: &rkyv::vec::ArchivedVec<u32>| {
+        ids.iter()
+            .zip(counts.iter())
+            .map(|(&id, &count)| RecipeItem::new(ItemId::new(id), count))
+            .collect_vec()
+    };
+
+    Ok(archived
+        .special_shops
+        .iter()
+        .flat_map(|shop| {
+            shop.entries.iter().map(|entry| {
+                CurrencyExchange::new(
+                    SpecialShopId::new(shop.id),
+                    to_items(&entry.receive_item_ids, &entry.receive_counts),
+                    to_items(&entry.cost_item_ids, &entry.cost_counts),
+                )
+            })
+        })
+        .collect_vec())
+}
+
+pub fn get_gathering_item_indices_by_item(item_id: ItemId) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices_of(&indices.gathering_items_by_item, item_id.into()),
+        None => ITEM_TO_GATHERING_ITEM_INDICES
+            .get(&item_id.into())
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+pub fn get_gathering_point_indices_by_gathering_item(id: GatheringItemId) -> &'static [usize] {
+    match loaded_indices() {
+        Some(indices) => indices_of(&indices.points_by_gathering_item, id.into()),
+        None => GATHERING_ITEM_TO_POINT_INDICES
+            .get(&id.into())
+            .copied()
+            .unwrap_or(&[]),
+    }
+}
+
+pub fn get_collectable_reward_index_by_item(item_id: ItemId) -> Option<usize> {
+    match loaded_indices() {
+        Some(indices) => index_of(&indices.collectable_reward_by_item, item_id.into()),
+        None => COLLECTABLE_ITEM_TO_REWARD_INDEX
+            .get(&item_id.into())
+            .copied(),
+    }
+}
+
+pub fn read_gathering_items() -> Result<Vec<GatheringItem>> {
+    let archived = get_archived_data()?;
+
+    Ok(archived
+        .gathering_items
+        .iter()
+        .map(|row| {
+            GatheringItem::new(
+                GatheringItemId::new(row.id),
+                ItemId::new(row.item_id),
+                row.level,
+                row.stars,
+                row.is_hidden,
+            )
+        })
+        .collect_vec())
+}
+
+pub fn read_gathering_points() -> Result<Vec<GatheringPoint>> {
+    let archived = get_archived_data()?;
+
+    archived
+        .gathering_points
+        .iter()
+        .map(|row| {
+            let spawn_windows = row
+                .spawn_starts
+                .iter()
+                .zip(row.spawn_durations.iter())
+                .map(|(&start, &duration)| SpawnWindow::new(start, duration))
+                .collect_vec();
+
+            Ok(GatheringPoint {
+                id: GatheringPointId::new(row.id),
+                gathering_type: GatheringType::from(row.gathering_type)?,
+                level: row.level,
+                items: row
+                    .gathering_item_ids
+                    .iter()
+                    .map(|&id| GatheringItemId::new(id))
+                    .collect_vec(),
+                territory: TerritoryTypeId::new(row.territory_type),
+                place_name: Some(row.place_name.to_string()).filter(|p| !p.is_empty()),
+                is_limited: row.is_limited,
+                spawn_windows,
+                is_ephemeral: row.is_ephemeral,
+            })
+        })
+        .collect()
+}
+
+pub fn read_collectable_rewards() -> Result<Vec<CollectableReward>> {
+    let archived = get_archived_data()?;
+
+    archived
+        .collectable_rewards
+        .iter()
+        .map(|row| {
+            let collectability: [u16; 3] =
+                row.collectability.as_slice().try_into().map_err(|_| {
+                    eyre!(
+                        "expected 3 collectability thresholds for item {}",
+                        row.item_id
+                    )
+                })?;
+            let rewards: [u32; 3] = row
+                .rewards
+                .as_slice()
+                .try_into()
+                .map_err(|_| eyre!("expected 3 rewards for item {}", row.item_id))?;
+            Ok(CollectableReward::new(
+                ItemId::new(row.item_id),
+                ItemId::new(row.currency_item_id),
+                collectability,
+                rewards,
+            ))
+        })
+        .collect()
+}
+
+pub fn read_foods() -> Result<Vec<Food>> {
+    let archived = get_archived_data()?;
+
+    archived
+        .foods
+        .iter()
+        .map(|row| {
+            let item_id = ItemId::new(row.item_id);
+            let name = get_item_index_by_id(item_id)
+                .map(|i| archived.items[i].name.to_string())
+                .ok_or_else(|| eyre!("Missing item {} for food", item_id))?;
+            let bonuses = (0..row.base_params.len())
+                .map(|i| {
+                    FoodBonus::new(
+                        BaseParam::from(row.base_params[i]),
+                        row.is_relative[i],
+                        row.values[i],
+                        row.maxes[i],
+                        row.values_hq[i],
+                        row.maxes_hq[i],
+                    )
+                })
+                .collect_vec();
+            Ok(Food::new(item_id, name, row.is_medicine, bonuses))
+        })
+        .collect()
+}
+
+pub fn read_company_crafts() -> Result<Vec<CompanyCraft>> {
+    let archived = get_archived_data()?;
+
+    Ok(archived
+        .company_crafts
+        .iter()
+        .map(|row| {
+            let phases = row
+                .phases
+                .iter()
+                .map(|phase| {
+                    let items = phase
+                        .item_ids
+                        .iter()
+                        .zip(phase.amounts.iter())
+                        .map(|(&id, &amount)| RecipeItem::new(ItemId::new(id), amount))
+                        .collect_vec();
+                    CompanyCraftPhase::new(phase.part, items)
+                })
+                .collect_vec();
+            CompanyCraft::new(
+                CompanyCraftId::new(row.id),
+                ItemId::new(row.result_item_id),
+                phases,
+            )
+        })
+        .collect_vec())
 }
 
-pub fn get_item_index_by_name(name: &str) -> Option<usize> {
-    ITEM_NAME_TO_INDEX.get(name).copied()
+pub fn read_territories() -> Result<Vec<Territory>> {
+    let archived = get_archived_data()?;
+
+    Ok(archived
+        .territories
+        .iter()
+        .map(|row| {
+            Territory::new(
+                TerritoryTypeId::new(row.id),
+                row.name.to_string(),
+                WeatherRateId::new(row.weather_rate),
+            )
+        })
+        .collect_vec())
 }
 
-pub fn get_recipe_index_by_result_item(item_id: ItemId) -> Option<usize> {
-    RECIPE_RESULT_TO_INDEX.get(&item_id.into()).copied()
+pub fn read_weather_rates() -> Result<Vec<WeatherRate>> {
+    let archived = get_archived_data()?;
+
+    Ok(archived
+        .weather_rates
+        .iter()
+        .map(|row| {
+            let chances = row
+                .weather_ids
+                .iter()
+                .zip(row.rates.iter())
+                .map(|(&weather, &rate)| (WeatherId::new(weather), rate))
+                .collect_vec();
+            WeatherRate::new(WeatherRateId::new(row.id), chances)
+        })
+        .collect_vec())
+}
+
+pub fn read_weathers() -> Result<Vec<Weather>> {
+    let archived = get_archived_data()?;
+
+    Ok(archived
+        .weathers
+        .iter()
+        .map(|row| Weather::new(WeatherId::new(row.id), row.name.to_string()))
+        .collect_vec())
+}
+
+pub fn read_craft_leves() -> Result<Vec<Leve>> {
+    let archived = get_archived_data()?;
+
+    archived
+        .craft_leves
+        .iter()
+        .map(|row| {
+            let items = row
+                .item_ids
+                .iter()
+                .zip(row.item_counts.iter())
+                .map(|(&id, &count)| RecipeItem::new(ItemId::new(id), count))
+                .collect_vec();
+            let reward_items = (0..row.reward_item_ids.len())
+                .map(|i| {
+                    LeveRewardItem::new(
+                        ItemId::new(row.reward_item_ids[i]),
+                        row.reward_counts[i],
+                        row.reward_hq[i],
+                        row.reward_probabilities[i],
+                    )
+                })
+                .collect_vec();
+
+            Ok(Leve {
+                id: LeveId::new(row.id),
+                name: row.name.to_string(),
+                job: CraftingJob::from(row.craft_type)?,
+                level: row.class_job_level,
+                exp_reward: row.exp_reward,
+                gil_reward: row.gil_reward,
+                repeats: row.repeats,
+                items,
+                reward_items,
+            })
+        })
+        .collect()
 }
 
 pub fn read_materia() -> Result<Vec<Materia>> {
-    let archived = get_archived_data();
+    let archived = get_archived_data()?;
 
     archived
         .materia
@@ -157,7 +748,85 @@ pub fn read_materia() -> Result<Vec<Materia>> {
                 .filter(|ml| ml.item_id != ItemId::ZERO)
                 .collect_vec();
 
-            Ok(Materia::new(MateriaId::new(row.id), levels))
+            Ok(Materia::new(
+                MateriaId::new(row.id),
+                levels,
+                BaseParam::from(row.base_param),
+            ))
         })
         .collect()
 }
+
+/// `gather_craft` picks the rates for crafting and gathering gear
+pub fn read_materia_join_rates(gather_craft: bool) -> Result<Vec<MateriaJoinRate>> {
+    let archived = get_archived_data()?;
+    let rows = if gather_craft {
+        &archived.materia_join_rates_gather_craft
+    } else {
+        &archived.materia_join_rates
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| MateriaJoinRate::new((row.id + 1) as u8, row.nq.to_vec(), row.hq.to_vec()))
+        .collect_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_files_from_other_builds_are_rejected() {
+        let mut file = DATA_FILE_MAGIC.to_vec();
+        file.extend_from_slice(&(DATA_SCHEMA_HASH ^ 1).to_le_bytes());
+        file.extend_from_slice(&[0; 64]);
+
+        assert!(strip_header(&file)
+            .unwrap_err()
+            .to_string()
+            .contains("doesn't match this build"));
+        assert!(strip_header(b"not a data file at all").is_err());
+        assert!(strip_header(&file[..10]).is_err());
+    }
+
+    #[test]
+    fn corrupt_archives_fail_validation() {
+        let mut bytes = serialize(&EmbeddedData::default()).unwrap();
+        // every Vec's relative pointer and length now points way outside the buffer
+        bytes.fill(0xff);
+
+        assert!(rkyv::check_archived_root::<EmbeddedData>(&bytes).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-data")]
+    fn runtime_indices_match_the_generated_maps() {
+        let indices = DataIndices::build(get_archived_data().unwrap());
+
+        for (&id, &idx) in &indices.item_by_id {
+            assert_eq!(ITEM_ID_TO_INDEX.get(&id), Some(&idx));
+        }
+        for (key, idxs) in &indices.items_by_search_key[0] {
+            assert_eq!(
+                ITEM_SEARCH_KEY_TO_INDICES.get(key.as_str()),
+                Some(&idxs.as_slice())
+            );
+        }
+        for (&id, idxs) in &indices.recipes_by_result {
+            assert_eq!(RECIPE_RESULT_TO_INDICES.get(&id), Some(&idxs.as_slice()));
+        }
+        for (&id, idxs) in &indices.recipes_by_ingredient {
+            assert_eq!(
+                RECIPE_INGREDIENT_TO_INDICES.get(&id),
+                Some(&idxs.as_slice())
+            );
+        }
+        assert_eq!(indices.item_by_id.len(), ITEM_ID_TO_INDEX.len());
+        assert_eq!(indices.item_by_name[0].len(), ITEM_NAME_TO_INDEX.len());
+        assert_eq!(
+            indices.gil_shops_by_item.len(),
+            GIL_SHOP_ITEM_TO_SHOP_INDICES.len()
+        );
+    }
+}
@@ -1,64 +1,73 @@
-use crate::model::ItemId;
-use color_eyre::eyre::Result;
+use crate::model::{CraftingJob, ItemId, LeveId, RecipeItem};
+use derive_more::Constructor;
 use itertools::Itertools;
-use serde_json::Value;
 
-#[derive(Debug)]
-pub struct Leve {
-    pub quest_level: u8,
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct LeveRewardItem {
     pub item_id: ItemId,
-    pub item_name: String,
-    pub item_count: u8,
+    pub amount: u32,
+    pub is_hq: bool,
+    /// percent chance of getting this reward
+    pub probability: u8,
+}
+
+/// A crafting leve
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Leve {
+    pub id: LeveId,
+    pub name: String,
+    pub job: CraftingJob,
+    pub level: u8,
+    pub exp_reward: u32,
     pub gil_reward: u32,
-    pub leve_name: String,
+    /// how many extra times we can hand the items in, for the reward each time
+    pub repeats: u8,
+    pub items: Vec<RecipeItem>,
+    pub reward_items: Vec<LeveRewardItem>,
+}
+
+impl Leve {
+    fn turn_ins(&self) -> u32 {
+        u32::from(self.repeats) + 1
+    }
+
+    /// gil for every turn-in. HQ turn-ins get double
+    pub fn total_gil(&self, hq: bool) -> u32 {
+        self.gil_reward * self.turn_ins() * if hq { 2 } else { 1 }
+    }
+
+    /// exp for every turn-in. HQ turn-ins get double
+    pub fn total_exp(&self, hq: bool) -> u32 {
+        self.exp_reward * self.turn_ins() * if hq { 2 } else { 1 }
+    }
+
+    /// everything we need to hand in, including the repeats
+    pub fn total_items(&self) -> Vec<RecipeItem> {
+        self.items.iter().map(|i| i * self.turn_ins()).collect_vec()
+    }
 }
 
-pub fn get_relevant_leves() -> Result<Vec<Leve>> {
-    let leve_data: Value = serde_json::from_str(include_str!("../data/Leve.json"))?;
-    let leve_data = leve_data.as_object().unwrap();
-    let leves = leve_data.get("Results").unwrap().as_array().unwrap();
-    let leves = leves
-        .iter()
-        .map(|l| Leve {
-            quest_level: l.get("ClassJobLevel").unwrap().as_u64().unwrap() as u8,
-            item_id: ItemId::new(
-                l.get("CraftLeve")
-                    .unwrap()
-                    .get("Item0")
-                    .unwrap()
-                    .get("ID")
-                    .unwrap()
-                    .as_u64()
-                    .unwrap_or(0) as i32,
-            ),
-            item_name: l
-                .get("CraftLeve")
-                .unwrap()
-                .get("Item0")
-                .unwrap()
-                .get("Name")
-                .unwrap()
-                .as_str()
-                .unwrap_or("")
-                .to_string(),
-            item_count: l
-                .get("CraftLeve")
-                .unwrap()
-                .get("ItemCount0")
-                .unwrap()
-                .as_u64()
-                .unwrap_or(0) as u8,
-            gil_reward: l.get("GilReward").unwrap().as_u64().unwrap() as u32,
-            leve_name: l.get("Name").unwrap().as_str().unwrap().to_string(),
-        })
-        .filter(|l| {
-            l.gil_reward > 0
-                && l.quest_level >= 80
-                && l.item_count > 0
-                && !l.item_name.is_empty()
-                && l.item_id != ItemId::ZERO
-        })
-        .collect_vec();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_multiply_the_rewards_and_items() {
+        let leve = Leve {
+            id: LeveId::new(1),
+            name: "Test".to_string(),
+            job: CraftingJob::Culinarian,
+            level: 90,
+            exp_reward: 1000,
+            gil_reward: 500,
+            repeats: 2,
+            items: vec![RecipeItem::new(ItemId::new(5), 3)],
+            reward_items: vec![],
+        };
 
-    Ok(leves)
+        assert_eq!(1500, leve.total_gil(false));
+        assert_eq!(3000, leve.total_gil(true));
+        assert_eq!(6000, leve.total_exp(true));
+        assert_eq!(vec![RecipeItem::new(ItemId::new(5), 9)], leve.total_items());
+    }
 }
@@ -1,6 +1,11 @@
+pub mod company_craft;
+pub mod datamining;
 pub mod embedded_data;
 pub mod food;
-mod jsonext;
 pub mod leve;
 pub mod lookup;
+pub mod materials;
+pub mod melding;
 pub mod model;
+pub mod search;
+pub mod weather;
@@ -1,15 +1,55 @@
-use crate::{embedded_data, model::*};
-use color_eyre::Result;
+use crate::{
+    company_craft::CompanyCraft, embedded_data, leve::Leve, materials, materials::*, melding,
+    melding::*, model::*, search, weather,
+};
+use color_eyre::{eyre::eyre, Result};
 use derive_more::Constructor;
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use std::path::Path;
 
 #[derive(Debug, Constructor)]
 pub struct ItemLookup {
     items: Vec<Item>,
+    /// which language `display_name` uses
+    display_language: Language,
 }
 
 impl ItemLookup {
     pub fn from_embedded() -> Result<ItemLookup> {
-        Ok(ItemLookup::new(embedded_data::read_items()?))
+        Ok(ItemLookup::new(
+            embedded_data::read_items()?,
+            Language::default(),
+        ))
+    }
+
+    /// Reads a datamining checkout (or data file) at runtime instead, see `embedded_data::load_from`.
+    /// The data is loaded once for the whole process, so this fails if some data has already
+    /// been read (eg by another `from_embedded`), or was loaded from a different path.
+    pub fn from_dir(path: &Path) -> Result<ItemLookup> {
+        embedded_data::load_from(path)?;
+        ItemLookup::from_embedded()
+    }
+
+    pub fn with_display_language(self, display_language: Language) -> ItemLookup {
+        ItemLookup {
+            display_language,
+            ..self
+        }
+    }
+
+    pub fn display_language(&self) -> Language {
+        self.display_language
+    }
+
+    /// eg "3 iron ingots" in english. The other languages don't have the same
+    /// singular/plural forms in the csvs, so we just use the plain item name.
+    pub fn display_name<'a>(&self, item: &'a Item, amount: u32) -> &'a str {
+        match self.display_language {
+            Language::English if amount > 1 => &item.name_plural,
+            Language::English => &item.name_singular,
+            other => item.name_in(other),
+        }
     }
 
     pub fn all(&self) -> impl Iterator<Item = &Item> {
@@ -26,17 +66,79 @@ impl ItemLookup {
         &self.items[index]
     }
 
+    pub fn item_by_id_opt(&self, id: ItemId) -> Option<&Item> {
+        embedded_data::get_item_index_by_id(id).and_then(|index| self.items.get(index))
+    }
+
+    /// For names hard-coded in english. Use `find_item_by_name` for user input.
     pub fn item_by_name(&self, name: &str) -> &Item {
-        self.item_by_name_opt(name).unwrap()
+        self.find_item_by_name(name, Language::English)
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
     pub fn item_by_name_opt(&self, name: &str) -> Option<&Item> {
-        let index = embedded_data::get_item_index_by_name(name)?;
+        self.item_by_name_in(name, Language::English)
+    }
+
+    pub fn item_by_name_in(&self, name: &str, language: Language) -> Option<&Item> {
+        let index = embedded_data::get_item_index_by_name(name, language)?;
         self.items.get(index)
     }
 
+    /// Tries the exact name first, then falls back to a case/accent-insensitive match.
+    /// English names are accepted too, whatever the language.
+    /// If nothing matches, the error includes some "did you mean" suggestions.
+    pub fn find_item_by_name(&self, name: &str, language: Language) -> Result<&Item> {
+        let key = search::search_key(name);
+        for lang in [language, Language::English] {
+            if let Some(item) = self.item_by_name_in(name, lang) {
+                return Ok(item);
+            }
+            if let Some(&index) = embedded_data::get_item_indices_by_search_key(&key, lang).first()
+            {
+                return Ok(&self.items[index]);
+            }
+        }
+
+        let suggestions = self.suggestions(name, language, 5);
+        if suggestions.is_empty() {
+            Err(eyre!("Could not find item '{}'", name))
+        } else {
+            Err(eyre!(
+                "Could not find item '{}'. Did you mean: {}?",
+                name,
+                suggestions
+                    .iter()
+                    .map(|i| format!("'{}'", i.name_in(language)))
+                    .join(", ")
+            ))
+        }
+    }
+
+    /// Items matching `query` (ignoring case, accents and small typos), best matches first
+    pub fn search(&self, query: &str, language: Language) -> impl Iterator<Item = &Item> {
+        search::search_item_indices(query, language)
+            .into_iter()
+            .map(|i| &self.items[i])
+    }
+
+    /// Like `search`, but with at most `limit` results and no repeated names
+    pub fn suggestions(&self, query: &str, language: Language, limit: usize) -> Vec<&Item> {
+        search::suggest_item_indices(query, language, limit)
+            .into_iter()
+            .map(|i| &self.items[i])
+            .collect_vec()
+    }
+
+    /// Case-insensitive substring search over (english) item names
     pub fn name_containing<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Item> + 'a {
-        self.matching(move |i| i.name.contains(name))
+        let query = search::search_key(name);
+        embedded_data::item_search_keys(Language::English)
+            .filter(move |(key, _)| key.contains(&query))
+            .flat_map(|(_, indices)| indices.iter().copied())
+            // keep the same order as the item list
+            .sorted()
+            .map(|i| &self.items[i])
     }
 }
 
@@ -50,24 +152,449 @@ impl RecipeLookup {
         Ok(RecipeLookup::new(embedded_data::read_recipes()?))
     }
 
+    /// Reads a datamining checkout (or data file) at runtime instead, see `embedded_data::load_from`.
+    /// The data is loaded once for the whole process, so this fails if some data has already
+    /// been read (eg by another `from_embedded`), or was loaded from a different path.
+    pub fn from_dir(path: &Path) -> Result<RecipeLookup> {
+        embedded_data::load_from(path)?;
+        RecipeLookup::from_embedded()
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+
+    pub fn matching(&self, predicate: impl Fn(&&Recipe) -> bool) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter().filter(predicate)
+    }
+
+    pub fn for_job(&self, job: CraftingJob) -> impl Iterator<Item = &Recipe> {
+        self.matching(move |r| r.job == job)
+    }
+
+    /// eg all the WVR recipes at rlvl 690
+    pub fn for_job_at_rlvl(
+        &self,
+        job: CraftingJob,
+        rlvl: RecipeLevelId,
+    ) -> impl Iterator<Item = &Recipe> {
+        self.matching(move |r| r.job == job && r.rlvl.rlvl == rlvl)
+    }
+
+    /// uses the job level as shown in the recipe book, rather than the rlvl
+    pub fn for_job_at_level(
+        &self,
+        job: CraftingJob,
+        class_job_level: u8,
+    ) -> impl Iterator<Item = &Recipe> {
+        self.matching(move |r| r.job == job && r.rlvl.class_job_level == class_job_level)
+    }
+
+    pub fn for_job_with_stars(&self, job: CraftingJob, stars: u8) -> impl Iterator<Item = &Recipe> {
+        self.matching(move |r| r.job == job && r.rlvl.stars == stars)
+    }
+
+    /// The first recipe that produces this item. Use `recipes_for_item` if it
+    /// matters which job's recipe (or the collectable version) you get.
     pub fn recipe_for_item(&self, id: ItemId) -> Option<&Recipe> {
-        let index = embedded_data::get_recipe_index_by_result_item(id)?;
+        self.recipes_for_item(id).next()
+    }
+
+    /// The raw materials for crafting `amount` of `item`, grouped by how far down the
+    /// crafting tree they're needed. `None` if the item isn't craftable
+    pub fn bill_of_materials(
+        &self,
+        item: ItemId,
+        amount: u32,
+        policy: &MaterialsPolicy,
+    ) -> Option<BillOfMaterials> {
+        let recipe = self.recipe_for_item(item)?;
+        Some(materials::bill_of_materials(recipe, amount, policy, |id| {
+            self.recipe_for_item(id)
+        }))
+    }
+
+    /// All recipes that produce this item
+    pub fn recipes_for_item(&self, id: ItemId) -> impl Iterator<Item = &Recipe> {
+        embedded_data::get_recipe_indices_by_result_item(id)
+            .iter()
+            .filter_map(|&index| self.recipes.get(index))
+    }
+
+    /// All recipes that have this item as an ingredient
+    pub fn recipes_using(&self, id: ItemId) -> impl Iterator<Item = &Recipe> {
+        embedded_data::get_recipe_indices_by_ingredient(id)
+            .iter()
+            .filter_map(|&index| self.recipes.get(index))
+    }
+
+    pub fn recipe_by_id(&self, id: RecipeId) -> Option<&Recipe> {
+        let index = embedded_data::get_recipe_index_by_id(id)?;
         self.recipes.get(index)
     }
 }
 
+#[derive(Debug, Constructor)]
+pub struct VendorLookup {
+    shops: Vec<GilShop>,
+}
+
+impl VendorLookup {
+    pub fn from_embedded() -> Result<VendorLookup> {
+        Ok(VendorLookup::new(embedded_data::read_gil_shops()?))
+    }
+
+    pub fn shops_selling(&self, id: ItemId) -> impl Iterator<Item = &GilShop> {
+        embedded_data::get_gil_shop_indices_by_item(id)
+            .iter()
+            .filter_map(|&index| self.shops.get(index))
+    }
+
+    /// The NPCs we can buy this item from. Some shops don't have any NPCs we know
+    /// about (eg ones that only open after a quest), so this can be empty even
+    /// when `vendor_price` isn't.
+    pub fn vendors_selling(&self, id: ItemId) -> impl Iterator<Item = &Vendor> {
+        self.shops_selling(id)
+            .flat_map(|s| s.vendors.iter())
+            .unique_by(|v| v.id)
+    }
+
+    /// Gil shops all charge the same price for an item, which lives on the item itself
+    pub fn vendor_price(&self, item: &Item) -> Option<u32> {
+        self.shops_selling(item.id)
+            .next()
+            .map(|_| item.vendor_price)
+    }
+}
+
+#[derive(Debug, Constructor)]
+pub struct CurrencyExchangeLookup {
+    exchanges: Vec<CurrencyExchange>,
+}
+
+impl CurrencyExchangeLookup {
+    pub fn from_embedded() -> Result<CurrencyExchangeLookup> {
+        Ok(CurrencyExchangeLookup::new(
+            embedded_data::read_currency_exchanges()?,
+        ))
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &CurrencyExchange> {
+        self.exchanges.iter()
+    }
+
+    /// Trades that only cost `currency`, ie everything we can buy with it.
+    /// The same trade often shows up in several shops, so callers may want to dedupe.
+    pub fn exchanges_costing(&self, currency: ItemId) -> impl Iterator<Item = &CurrencyExchange> {
+        self.exchanges
+            .iter()
+            .filter(move |e| matches!(e.cost.as_slice(), [c] if c.item_id == currency))
+    }
+
+    /// Trades that give us `item`, eg turning Ceremonial gear into certificates
+    pub fn exchanges_rewarding(&self, item: ItemId) -> impl Iterator<Item = &CurrencyExchange> {
+        self.exchanges
+            .iter()
+            .filter(move |e| e.receive.iter().any(|r| r.item_id == item))
+    }
+}
+
+#[derive(Debug, Constructor)]
+pub struct GatheringLookup {
+    items: Vec<GatheringItem>,
+    points: Vec<GatheringPoint>,
+    collectables: Vec<CollectableReward>,
+}
+
+impl GatheringLookup {
+    pub fn from_embedded() -> Result<GatheringLookup> {
+        Ok(GatheringLookup::new(
+            embedded_data::read_gathering_items()?,
+            embedded_data::read_gathering_points()?,
+            embedded_data::read_collectable_rewards()?,
+        ))
+    }
+
+    pub fn gathering_items_for(&self, item_id: ItemId) -> impl Iterator<Item = &GatheringItem> {
+        embedded_data::get_gathering_item_indices_by_item(item_id)
+            .iter()
+            .filter_map(|&index| self.items.get(index))
+    }
+
+    pub fn is_gatherable(&self, item_id: ItemId) -> bool {
+        self.gathering_items_for(item_id).next().is_some()
+    }
+
+    /// Every point that has `item_id` on it
+    pub fn points_for(&self, item_id: ItemId) -> impl Iterator<Item = &GatheringPoint> {
+        self.gathering_items_for(item_id)
+            .flat_map(|gi| embedded_data::get_gathering_point_indices_by_gathering_item(gi.id))
+            .filter_map(|&index| self.points.get(index))
+            .unique_by(|p| p.id)
+    }
+
+    /// Everything else gathered on the same points as `item_id`
+    pub fn gathered_alongside(&self, item_id: ItemId) -> impl Iterator<Item = &GatheringItem> {
+        self.points_for(item_id)
+            .flat_map(|p| p.items.iter())
+            .unique()
+            .filter_map(|&id| self.items.iter().find(|gi| gi.id == id))
+            .filter(move |gi| gi.item_id != item_id)
+    }
+
+    pub fn collectable_reward(&self, item_id: ItemId) -> Option<&CollectableReward> {
+        embedded_data::get_collectable_reward_index_by_item(item_id)
+            .and_then(|index| self.collectables.get(index))
+    }
+
+    /// Collectables we can gather (rather than craft), eg everything for a gatherers' scrip
+    pub fn gatherable_collectables(&self) -> impl Iterator<Item = &CollectableReward> {
+        self.collectables
+            .iter()
+            .filter(|c| self.is_gatherable(c.item_id))
+    }
+}
+
+#[derive(Debug, Constructor)]
+pub struct WeatherLookup {
+    territories: Vec<Territory>,
+    rates: FxHashMap<WeatherRateId, WeatherRate>,
+    weathers: FxHashMap<WeatherId, Weather>,
+}
+
+impl WeatherLookup {
+    pub fn from_embedded() -> Result<WeatherLookup> {
+        Ok(WeatherLookup::new(
+            embedded_data::read_territories()?,
+            embedded_data::read_weather_rates()?
+                .into_iter()
+                .map(|r| (r.id, r))
+                .collect(),
+            embedded_data::read_weathers()?
+                .into_iter()
+                .map(|w| (w.id, w))
+                .collect(),
+        ))
+    }
+
+    /// Zones with the same name (eg instanced copies) share their weather, so the first one is fine
+    pub fn territory_by_name(&self, name: &str) -> Result<&Territory> {
+        self.territories
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let suggestions = self
+                    .territories
+                    .iter()
+                    .filter(|t| t.name.to_lowercase().contains(&name.to_lowercase()))
+                    .map(|t| &t.name)
+                    .unique()
+                    .take(5)
+                    .join(", ");
+                if suggestions.is_empty() {
+                    eyre!("Couldn't find a zone with weather called {}", name)
+                } else {
+                    eyre!(
+                        "Couldn't find a zone with weather called {}. Did you mean: {}",
+                        name,
+                        suggestions
+                    )
+                }
+            })
+    }
+
+    pub fn territory(&self, id: TerritoryTypeId) -> Option<&Territory> {
+        self.territories.iter().find(|t| t.id == id)
+    }
+
+    /// The weathers that can happen in a zone
+    pub fn possible_weathers(&self, territory: &Territory) -> impl Iterator<Item = &Weather> {
+        self.rates
+            .get(&territory.weather_rate)
+            .into_iter()
+            .flat_map(|r| r.chances.iter())
+            .filter_map(|(id, _)| self.weathers.get(id))
+    }
+
+    /// The weather in a zone at some (unix) time
+    pub fn weather_at(&self, territory: &Territory, unix_seconds: i64) -> Option<&Weather> {
+        let rate = self.rates.get(&territory.weather_rate)?;
+        let weather = rate.weather_for(weather::forecast_target(unix_seconds))?;
+        self.weathers.get(&weather)
+    }
+
+    /// The zone's rates, as long as they give a weather we know about for every period.
+    /// Otherwise searching the forecast could go on forever.
+    fn usable_rate(&self, territory: &Territory) -> Result<&WeatherRate> {
+        let rate = self
+            .rates
+            .get(&territory.weather_rate)
+            .ok_or_else(|| eyre!("No weather rates for {}", territory.name))?;
+        let total: u32 = rate.chances.iter().map(|&(_, chance)| chance as u32).sum();
+        if total < 100 {
+            return Err(eyre!(
+                "The weather rates for {} only add up to {}%",
+                territory.name,
+                total
+            ));
+        }
+        if let Some((id, _)) = rate
+            .chances
+            .iter()
+            .find(|(id, chance)| *chance > 0 && !self.weathers.contains_key(id))
+        {
+            return Err(eyre!("{} has an unknown weather {}", territory.name, id));
+        }
+        Ok(rate)
+    }
+
+    /// Each weather period from the one containing `unix_seconds` onwards, with their start times
+    pub fn forecast<'a>(
+        &'a self,
+        territory: &'a Territory,
+        unix_seconds: i64,
+    ) -> Result<impl Iterator<Item = (i64, &'a Weather)> + 'a> {
+        self.usable_rate(territory)?;
+
+        Ok((0..)
+            .map(move |i| weather::period_start(unix_seconds) + i * weather::PERIOD_SECONDS)
+            .map_while(move |start| Some((start, self.weather_at(territory, start)?))))
+    }
+
+    /// Start times of the periods after `unix_seconds` where the weather follows `sequence`,
+    /// eg ["Rain", "Fair Skies"] for fair skies straight after rain. The times are for the
+    /// start of the last weather in the sequence.
+    pub fn find_sequence<'a>(
+        &'a self,
+        territory: &'a Territory,
+        sequence: &'a [&str],
+        unix_seconds: i64,
+    ) -> Result<impl Iterator<Item = i64> + 'a> {
+        // otherwise we'd search forever
+        self.usable_rate(territory)?;
+        for name in sequence {
+            if !self
+                .possible_weathers(territory)
+                .any(|w| w.name.eq_ignore_ascii_case(name))
+            {
+                return Err(eyre!(
+                    "{} never happens in {}. Possible weathers: {}",
+                    name,
+                    territory.name,
+                    self.possible_weathers(territory)
+                        .map(|w| &w.name)
+                        .join(", ")
+                ));
+            }
+        }
+
+        let len = sequence.len() as i64;
+        Ok((0..)
+            .map(move |i| weather::period_start(unix_seconds) + i * weather::PERIOD_SECONDS)
+            .filter(move |&start| {
+                sequence.iter().enumerate().all(|(i, name)| {
+                    let period = start - (len - 1 - i as i64) * weather::PERIOD_SECONDS;
+                    self.weather_at(territory, period)
+                        .is_some_and(|w| w.name.eq_ignore_ascii_case(name))
+                })
+            }))
+    }
+}
+
+#[derive(Debug, Constructor)]
+pub struct LeveLookup {
+    leves: Vec<Leve>,
+}
+
+impl LeveLookup {
+    pub fn from_embedded() -> Result<LeveLookup> {
+        Ok(LeveLookup::new(embedded_data::read_craft_leves()?))
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &Leve> {
+        self.leves.iter()
+    }
+
+    pub fn matching(&self, predicate: impl Fn(&&Leve) -> bool) -> impl Iterator<Item = &Leve> {
+        self.leves.iter().filter(predicate)
+    }
+
+    pub fn for_job_in_levels(
+        &self,
+        job: CraftingJob,
+        levels: std::ops::RangeInclusive<u8>,
+    ) -> impl Iterator<Item = &Leve> {
+        self.matching(move |l| l.job == job && levels.contains(&l.level))
+    }
+}
+
 #[derive(Debug, Constructor)]
 pub struct MateriaLookup {
     materia: Vec<Materia>,
+    join_rates: Vec<MateriaJoinRate>,
+    gather_craft_join_rates: Vec<MateriaJoinRate>,
 }
 
 impl MateriaLookup {
     pub fn from_embedded() -> Result<MateriaLookup> {
         let materia = embedded_data::read_materia()?;
-        Ok(MateriaLookup::new(materia))
+        let join_rates = embedded_data::read_materia_join_rates(false)?;
+        let gather_craft_join_rates = embedded_data::read_materia_join_rates(true)?;
+        Ok(MateriaLookup::new(
+            materia,
+            join_rates,
+            gather_craft_join_rates,
+        ))
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Materia> {
         self.materia.iter()
     }
+
+    /// The materia this item is a grade of, and which grade
+    pub fn for_item(&self, item_id: ItemId) -> Option<(&Materia, &MateriaLevel)> {
+        self.materia
+            .iter()
+            .find_map(|m| m.level_for_item(item_id).map(|ml| (m, ml)))
+    }
+
+    pub fn join_rate(&self, level: u8, kind: GearKind) -> Option<&MateriaJoinRate> {
+        let rates = match kind {
+            GearKind::Battle => &self.join_rates,
+            GearKind::CraftingGathering => &self.gather_craft_join_rates,
+        };
+        rates.iter().find(|r| r.level == level)
+    }
+
+    /// How likely each meld in the plan is to succeed on a given kind of gear
+    pub fn expected_melds(&self, plan: &MeldPlan, kind: GearKind) -> Result<Vec<ExpectedMeld>> {
+        melding::expected_melds(
+            plan,
+            |item_id| self.for_item(item_id).map(|(_, ml)| ml.level),
+            |level| self.join_rate(level, kind),
+        )
+    }
+}
+
+#[derive(Debug, Constructor)]
+pub struct CompanyCraftLookup {
+    crafts: Vec<CompanyCraft>,
+}
+
+impl CompanyCraftLookup {
+    pub fn from_embedded() -> Result<CompanyCraftLookup> {
+        Ok(CompanyCraftLookup::new(
+            embedded_data::read_company_crafts()?
+        ))
+    }
+
+    pub fn all(&self) -> &[CompanyCraft] {
+        &self.crafts
+    }
+
+    /// The project that builds this item
+    pub fn for_result(&self, item_id: ItemId) -> Option<&CompanyCraft> {
+        // there are only a few hundred projects, so this should be fast enough
+        self.crafts.iter().find(|c| c.result == item_id)
+    }
 }
@@ -0,0 +1,258 @@
+use crate::model::*;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Which items `RecipeLookup::bill_of_materials` should stop at
+#[derive(Debug, Clone)]
+pub struct MaterialsPolicy {
+    /// intermediates we'd rather buy than craft, so they count as raw materials
+    pub bought: FxHashSet<ItemId>,
+    pub include_crystals: bool,
+}
+
+impl Default for MaterialsPolicy {
+    fn default() -> Self {
+        MaterialsPolicy {
+            bought: FxHashSet::default(),
+            include_crystals: true,
+        }
+    }
+}
+
+impl MaterialsPolicy {
+    pub fn buying(self, items: impl IntoIterator<Item = ItemId>) -> MaterialsPolicy {
+        let mut bought = self.bought;
+        bought.extend(items);
+        MaterialsPolicy { bought, ..self }
+    }
+
+    pub fn excluding_crystals(self) -> MaterialsPolicy {
+        MaterialsPolicy {
+            include_crystals: false,
+            ..self
+        }
+    }
+
+    pub fn including_crystals(self) -> MaterialsPolicy {
+        MaterialsPolicy {
+            include_crystals: true,
+            ..self
+        }
+    }
+}
+
+/// What's needed at one step of the crafting tree
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MaterialsAtDepth {
+    /// things we need to gather or buy
+    pub raw: Vec<RecipeItem>,
+    /// things we need to craft, whose own ingredients are in the next depth down
+    pub crafted: Vec<RecipeItem>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BillOfMaterials {
+    /// `depths[0]` is the ingredients of the item itself, `depths[1]` the ingredients
+    /// of any of those that need crafting, and so on
+    pub depths: Vec<MaterialsAtDepth>,
+}
+
+impl BillOfMaterials {
+    /// The raw materials across every depth
+    pub fn total_raw(&self) -> Vec<RecipeItem> {
+        let mut total = vec![];
+        for ri in self.depths.iter().flat_map(|d| &d.raw) {
+            add_to(&mut total, ri.clone());
+        }
+        total
+    }
+}
+
+// deep enough for any real recipe, but stops us looping forever if recipes ever form a cycle
+const MAX_DEPTH: usize = 16;
+
+/// Each intermediate is placed at the deepest point it's needed in the tree, and only worked
+/// out once everything above it is, so the number of crafts is rounded up once for its total
+/// amount (rather than separately for each recipe that uses it).
+pub fn bill_of_materials<'a>(
+    recipe: &'a Recipe,
+    amount: u32,
+    policy: &MaterialsPolicy,
+    recipe_for_item: impl Fn(ItemId) -> Option<&'a Recipe>,
+) -> BillOfMaterials {
+    let sub_recipe =
+        |item_id: ItemId| recipe_for_item(item_id).filter(|_| !policy.bought.contains(&item_id));
+    let ingredients = |recipe: &'a Recipe| {
+        recipe
+            .ingredients
+            .iter()
+            .filter(|i| policy.include_crystals || !i.item_id.is_crystal())
+    };
+
+    // the depth of every intermediate, in the order we first came across them
+    let mut intermediates: Vec<(ItemId, &Recipe)> = vec![];
+    let mut depths: FxHashMap<ItemId, usize> = FxHashMap::default();
+    let mut to_visit = vec![(recipe, 0)];
+    while let Some((recipe, depth)) = to_visit.pop() {
+        for ingredient in ingredients(recipe) {
+            let Some(sub_recipe) = sub_recipe(ingredient.item_id) else {
+                continue;
+            };
+            match depths.get(&ingredient.item_id) {
+                Some(&known) if known >= depth => continue,
+                Some(_) => {}
+                None => intermediates.push((ingredient.item_id, sub_recipe)),
+            }
+            depths.insert(ingredient.item_id, depth);
+            if depth + 1 < MAX_DEPTH {
+                to_visit.push((sub_recipe, depth + 1));
+            }
+        }
+    }
+
+    // how many of each intermediate everything above it needs
+    let mut needed: FxHashMap<ItemId, u32> = FxHashMap::default();
+    let mut bom = BillOfMaterials::default();
+    let craft = |bom: &mut BillOfMaterials,
+                 needed: &mut FxHashMap<ItemId, u32>,
+                 recipe: &'a Recipe,
+                 amount: u32,
+                 depth: usize| {
+        let crafts = recipe.crafts_for(amount);
+        for ingredient in ingredients(recipe) {
+            let ri = ingredient * crafts;
+            if depths.contains_key(&ri.item_id) {
+                *needed.entry(ri.item_id).or_default() += ri.amount;
+            } else {
+                if bom.depths.len() <= depth {
+                    bom.depths.resize_with(depth + 1, MaterialsAtDepth::default);
+                }
+                add_to(&mut bom.depths[depth].raw, ri);
+            }
+        }
+    };
+
+    craft(&mut bom, &mut needed, recipe, amount, 0);
+    for depth in 0..MAX_DEPTH {
+        for &(item_id, recipe) in intermediates.iter().filter(|(id, _)| depths[id] == depth) {
+            let amount = needed.get(&item_id).copied().unwrap_or(0);
+            if bom.depths.len() <= depth {
+                bom.depths.resize_with(depth + 1, MaterialsAtDepth::default);
+            }
+            bom.depths[depth]
+                .crafted
+                .push(RecipeItem::new(item_id, amount));
+            craft(&mut bom, &mut needed, recipe, amount, depth + 1);
+        }
+    }
+
+    bom
+}
+
+fn add_to(items: &mut Vec<RecipeItem>, ri: RecipeItem) {
+    match items.iter_mut().find(|i| i.item_id == ri.item_id) {
+        Some(existing) => existing.amount += ri.amount,
+        None => items.push(ri),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRE_SHARD: i32 = 2;
+    const ORE: i32 = 100;
+    const NUGGET: i32 = 101;
+    const INGOT: i32 = 102;
+    const RIVETS: i32 = 103;
+    const PLATE: i32 = 104;
+
+    fn recipe(result: RecipeItem, ingredients: Vec<RecipeItem>) -> Recipe {
+        let rlvl = RecipeLevel {
+            rlvl: RecipeLevelId::new(1),
+            class_job_level: 1,
+            progress_divider: 50,
+            progress_modifier: 100,
+            quality_divider: 30,
+            quality_modifier: 100,
+            base_difficulty: 10,
+            base_durability: 60,
+            base_quality_target: 10,
+            stars: 0,
+        };
+        Recipe {
+            id: RecipeId::new(result.item_id.into()),
+            job: CraftingJob::Blacksmith,
+            ingredients,
+            result,
+            rlvl,
+            difficulty: 10,
+            durability: 60,
+            quality_target: 10,
+            required_craftsmanship: 0,
+            required_control: 0,
+        }
+    }
+
+    fn ri(item_id: i32, amount: u32) -> RecipeItem {
+        RecipeItem::new(ItemId::new(item_id), amount)
+    }
+
+    fn recipes() -> Vec<Recipe> {
+        vec![
+            // nuggets come in threes, so we'll have some left over
+            recipe(ri(NUGGET, 3), vec![ri(ORE, 1), ri(FIRE_SHARD, 1)]),
+            recipe(ri(INGOT, 1), vec![ri(ORE, 2), ri(FIRE_SHARD, 1)]),
+            recipe(ri(RIVETS, 1), vec![ri(NUGGET, 2), ri(FIRE_SHARD, 1)]),
+            recipe(
+                ri(PLATE, 1),
+                vec![
+                    ri(INGOT, 2),
+                    ri(RIVETS, 1),
+                    ri(NUGGET, 1),
+                    ri(FIRE_SHARD, 2),
+                ],
+            ),
+        ]
+    }
+
+    fn bom_for(item: i32, amount: u32, policy: &MaterialsPolicy) -> BillOfMaterials {
+        let recipes = recipes();
+        let find = |id: ItemId| recipes.iter().find(|r| r.result.item_id == id);
+        bill_of_materials(find(ItemId::new(item)).unwrap(), amount, policy, find)
+    }
+
+    #[test]
+    fn rounds_up_crafts_once_for_the_whole_tree() {
+        let bom = bom_for(PLATE, 2, &MaterialsPolicy::default());
+
+        assert_eq!(
+            bom.depths,
+            vec![
+                MaterialsAtDepth {
+                    raw: vec![ri(FIRE_SHARD, 4)],
+                    crafted: vec![ri(INGOT, 4), ri(RIVETS, 2)],
+                },
+                MaterialsAtDepth {
+                    raw: vec![ri(ORE, 8), ri(FIRE_SHARD, 6)],
+                    // 2 for the plates and 4 for the rivets, which is only 2 crafts
+                    crafted: vec![ri(NUGGET, 6)],
+                },
+                MaterialsAtDepth {
+                    raw: vec![ri(ORE, 2), ri(FIRE_SHARD, 2)],
+                    crafted: vec![],
+                },
+            ]
+        );
+        assert_eq!(bom.total_raw(), vec![ri(FIRE_SHARD, 12), ri(ORE, 10)]);
+    }
+
+    #[test]
+    fn bought_intermediates_and_crystals_can_be_left_out() {
+        let policy = MaterialsPolicy::default()
+            .buying([ItemId::new(RIVETS)])
+            .excluding_crystals();
+        let bom = bom_for(PLATE, 1, &policy);
+
+        assert_eq!(bom.total_raw(), vec![ri(RIVETS, 1), ri(ORE, 5)]);
+    }
+}
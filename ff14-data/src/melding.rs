@@ -0,0 +1,137 @@
+use crate::model::*;
+use color_eyre::{eyre::eyre, Result};
+use derive_more::Constructor;
+
+/// No gear takes more than five materia, even with overmelding
+pub const MAX_MATERIA_SLOTS: usize = 5;
+
+/// Crafting and gathering gear has its own, kinder, overmeld rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GearKind {
+    Battle,
+    CraftingGathering,
+}
+
+/// A piece of gear and the materia we want in it, in slot order
+#[derive(Debug, Clone, Constructor)]
+pub struct MeldPlan {
+    /// slots that always accept a meld, usually 2 for crafted gear
+    pub guaranteed_slots: usize,
+    pub hq: bool,
+    pub materia: Vec<ItemId>,
+}
+
+/// One materia going into one slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedMeld {
+    pub item_id: ItemId,
+    /// 0-based, counting the guaranteed slots
+    pub slot: usize,
+    pub success_chance: f64,
+}
+
+impl ExpectedMeld {
+    /// A failed meld uses up the materia, so on average we need 1/p of them
+    pub fn expected_materia(&self) -> f64 {
+        1.0 / self.success_chance
+    }
+}
+
+/// The chance of landing each meld in `plan`. `level_of` gives the grade of a materia item,
+/// and `join_rate` the overmeld rates for that grade.
+pub fn expected_melds<'a>(
+    plan: &MeldPlan,
+    level_of: impl Fn(ItemId) -> Option<u8>,
+    join_rate: impl Fn(u8) -> Option<&'a MateriaJoinRate>,
+) -> Result<Vec<ExpectedMeld>> {
+    if plan.materia.len() > MAX_MATERIA_SLOTS {
+        return Err(eyre!(
+            "Can't meld {} materia, gear takes at most {}",
+            plan.materia.len(),
+            MAX_MATERIA_SLOTS
+        ));
+    }
+
+    plan.materia
+        .iter()
+        .enumerate()
+        .map(|(slot, &item_id)| {
+            let level = level_of(item_id).ok_or_else(|| eyre!("Item {} isn't materia", item_id))?;
+            let success_chance = match slot.checked_sub(plan.guaranteed_slots) {
+                None => 1.0,
+                Some(overmeld) => {
+                    join_rate(level).map_or(0.0, |r| r.success_chance(overmeld, plan.hq))
+                }
+            };
+            if success_chance <= 0.0 {
+                return Err(eyre!(
+                    "Item {} (grade {}) can't be overmelded into slot {}",
+                    item_id,
+                    level,
+                    slot + 1
+                ));
+            }
+            Ok(ExpectedMeld {
+                item_id,
+                slot,
+                success_chance,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRADE_1: i32 = 10;
+    const GRADE_2: i32 = 20;
+
+    fn rates() -> Vec<MateriaJoinRate> {
+        vec![
+            MateriaJoinRate::new(1, vec![50, 25, 20, 10], vec![60, 30, 25, 15]),
+            MateriaJoinRate::new(2, vec![40, 0, 0, 0], vec![50, 0, 0, 0]),
+        ]
+    }
+
+    fn melds(plan: &MeldPlan) -> Result<Vec<ExpectedMeld>> {
+        let rates = rates();
+        let level_of = |id: ItemId| match Into::<i32>::into(id) {
+            GRADE_1 => Some(1),
+            GRADE_2 => Some(2),
+            _ => None,
+        };
+        expected_melds(plan, level_of, |level| {
+            rates.iter().find(|r| r.level == level)
+        })
+    }
+
+    fn plan(guaranteed_slots: usize, hq: bool, materia: &[i32]) -> MeldPlan {
+        MeldPlan::new(
+            guaranteed_slots,
+            hq,
+            materia.iter().map(|&id| ItemId::new(id)).collect(),
+        )
+    }
+
+    #[test]
+    fn pentameld_uses_the_rate_for_each_overmeld() {
+        let melds = melds(&plan(
+            2,
+            true,
+            &[GRADE_2, GRADE_2, GRADE_2, GRADE_1, GRADE_1],
+        ))
+        .unwrap();
+
+        let chances = melds.iter().map(|m| m.success_chance).collect::<Vec<_>>();
+        assert_eq!(chances, vec![1.0, 1.0, 0.5, 0.3, 0.25]);
+        assert_eq!(melds[4].expected_materia(), 4.0);
+    }
+
+    #[test]
+    fn rejects_melds_that_can_never_succeed() {
+        assert!(melds(&plan(2, true, &[GRADE_1, GRADE_1, GRADE_1, GRADE_2])).is_err());
+        assert!(melds(&plan(1, false, &[GRADE_1, 99])).is_err());
+        assert!(melds(&plan(2, false, &[GRADE_1; 6])).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use color_eyre::eyre::eyre;
 use color_eyre::{eyre::Context, Result};
 use derive_more::{Constructor, Display};
 use itertools::Itertools;
-use std::{iter, ops::Mul};
+use std::{iter, ops::Mul, str::FromStr};
 
 macro_rules! id {
     ($a:ident) => {
@@ -117,9 +117,113 @@ impl EquipSlotCategory {
     }
 }
 
+/// The crafting classes, in the same order as the CraftType sheet
+#[derive(Debug, Display, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum CraftingJob {
+    Carpenter = 0,
+    Blacksmith = 1,
+    Armorer = 2,
+    Goldsmith = 3,
+    Leatherworker = 4,
+    Weaver = 5,
+    Alchemist = 6,
+    Culinarian = 7,
+}
+
+impl CraftingJob {
+    pub const ALL: [CraftingJob; 8] = [
+        CraftingJob::Carpenter,
+        CraftingJob::Blacksmith,
+        CraftingJob::Armorer,
+        CraftingJob::Goldsmith,
+        CraftingJob::Leatherworker,
+        CraftingJob::Weaver,
+        CraftingJob::Alchemist,
+        CraftingJob::Culinarian,
+    ];
+
+    pub fn from(craft_type: u32) -> Result<CraftingJob> {
+        CraftingJob::ALL
+            .get(craft_type as usize)
+            .copied()
+            .ok_or_else(|| eyre!("unrecognised craft type {}", craft_type))
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            CraftingJob::Carpenter => "CRP",
+            CraftingJob::Blacksmith => "BSM",
+            CraftingJob::Armorer => "ARM",
+            CraftingJob::Goldsmith => "GSM",
+            CraftingJob::Leatherworker => "LTW",
+            CraftingJob::Weaver => "WVR",
+            CraftingJob::Alchemist => "ALC",
+            CraftingJob::Culinarian => "CUL",
+        }
+    }
+
+    pub fn from_abbreviation(abbreviation: &str) -> Result<CraftingJob> {
+        CraftingJob::ALL
+            .into_iter()
+            .find(|j| j.abbreviation().eq_ignore_ascii_case(abbreviation))
+            .ok_or_else(|| eyre!("unrecognised crafting job {}", abbreviation))
+    }
+}
+
+/// The languages that the game client (and so the datamining csvs) come in
+#[derive(Debug, Display, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+    German,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::Japanese,
+        Language::German,
+        Language::French,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Japanese => "ja",
+            Language::German => "de",
+            Language::French => "fr",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::ALL
+            .into_iter()
+            .find(|l| l.code().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                eyre!(
+                    "unrecognised language {} (expected one of en, ja, de, fr)",
+                    s
+                )
+            })
+    }
+}
+
 id!(ItemId);
 
-#[derive(Debug, PartialEq, Eq, Constructor)]
+impl ItemId {
+    /// shards, crystals and clusters, which almost every recipe needs
+    pub fn is_crystal(&self) -> bool {
+        (2..=19).contains(&self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct Item {
     pub id: ItemId,
     pub name: String,
@@ -128,13 +232,61 @@ pub struct Item {
     pub ilvl: u32,
     pub can_be_hq: bool,
     pub equip_slot_category: Option<EquipSlotCategory>,
+    pub is_collectable: bool,
+    pub localized_names: LocalizedNames,
+    pub stack_size: u32,
+    /// what NPC vendors charge for the item (Price{Mid} in the csvs).
+    /// Only meaningful if a vendor actually stocks it
+    pub vendor_price: u32,
+    /// what NPC vendors will pay for the item (Price{Low} in the csvs)
+    pub vendor_sell_price: u32,
+    pub is_untradable: bool,
+    /// the market board category, or 0 if it can't be listed on the market board
+    pub item_search_category: u32,
+    /// the category shown in the item tooltip
+    pub item_ui_category: u32,
+    /// 1 = white, 2 = green, 3 = blue, 4 = relic (purple), 7 = aetherial (pink)
+    pub rarity: u8,
+    pub equip_level: u16,
+    /// which jobs can equip or use the item
+    pub class_job_category: u32,
+}
+
+impl Item {
+    pub fn is_marketable(&self) -> bool {
+        !self.is_untradable && self.item_search_category != 0
+    }
+
+    /// falls back to the english name if we don't have a translation
+    pub fn name_in(&self, language: Language) -> &str {
+        let name = match language {
+            Language::English => &self.name,
+            Language::Japanese => &self.localized_names.ja,
+            Language::German => &self.localized_names.de,
+            Language::French => &self.localized_names.fr,
+        };
+        if name.is_empty() {
+            &self.name
+        } else {
+            name
+        }
+    }
+}
+
+/// Names in the other client languages (english lives directly on `Item`)
+#[derive(Debug, PartialEq, Eq, Default, Constructor)]
+pub struct LocalizedNames {
+    pub ja: String,
+    pub de: String,
+    pub fr: String,
 }
 
 id!(RecipeId);
 
-#[derive(Debug, PartialEq, Eq, Constructor)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Recipe {
     pub id: RecipeId,
+    pub job: CraftingJob,
     pub ingredients: Vec<RecipeItem>,
     pub result: RecipeItem,
 
@@ -161,6 +313,11 @@ impl Mul<u32> for &Recipe {
 }
 
 impl Recipe {
+    /// How many times we need to craft this to end up with at least `output_count` of the result
+    pub fn crafts_for(&self, output_count: u32) -> u32 {
+        output_count.div_ceil(self.result.amount)
+    }
+
     // TODO: is there a nice way to make the lifetimes work if we don't collect_vec() here?
     pub fn relevant_item_ids(&self, recipes: &RecipeLookup) -> impl Iterator<Item = ItemId> {
         iter::once(self.result.item_id)
@@ -174,7 +331,7 @@ impl Recipe {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Constructor)]
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
 pub struct RecipeItem {
     pub item_id: ItemId,
     pub amount: u32,
@@ -207,7 +364,14 @@ id!(MateriaId);
 pub struct Materia {
     pub materia_id: MateriaId,
     pub materia_levels: Vec<MateriaLevel>,
-    // pub base_param: BaseParamId,
+    /// the stat it adds to
+    pub base_param: BaseParam,
+}
+
+impl Materia {
+    pub fn level_for_item(&self, item_id: ItemId) -> Option<&MateriaLevel> {
+        self.materia_levels.iter().find(|ml| ml.item_id == item_id)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Constructor)]
@@ -217,11 +381,197 @@ pub struct MateriaLevel {
     pub bonus_value: i16,
 }
 
-id!(RecipeLevelId);
+/// The chances of overmelding one grade of materia, from the MateriaJoinRate sheets
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct MateriaJoinRate {
+    /// matches `MateriaLevel::level`
+    pub level: u8,
+    /// percentages, indexed by overmeld (0 is the first slot past the guaranteed ones)
+    pub nq: Vec<u8>,
+    pub hq: Vec<u8>,
+}
+
+impl MateriaJoinRate {
+    /// 0 if this grade can't go in that slot at all
+    pub fn success_chance(&self, overmeld: usize, hq: bool) -> f64 {
+        let rates = if hq { &self.hq } else { &self.nq };
+        rates.get(overmeld).map_or(0.0, |&r| f64::from(r) / 100.0)
+    }
+}
+
+id!(GilShopId);
+id!(ENpcId);
+
+/// An NPC that sells things for gil
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct Vendor {
+    pub id: ENpcId,
+    pub name: String,
+    /// eg "Limsa Lominsa Lower Decks", if we know where they stand
+    pub location: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Constructor)]
+pub struct GilShop {
+    pub id: GilShopId,
+    pub name: String,
+    pub items: Vec<ItemId>,
+    pub vendors: Vec<Vendor>,
+}
+
+id!(SpecialShopId);
+
+/// One trade in a SpecialShop, eg 1 Star Crew Jacket for 8400 Cosmocredits
+#[derive(Debug, PartialEq, Eq, Constructor)]
+pub struct CurrencyExchange {
+    pub shop_id: SpecialShopId,
+    pub receive: Vec<RecipeItem>,
+    pub cost: Vec<RecipeItem>,
+}
+
+/// The kinds of gathering point, in the same order as the GatheringType sheet
+#[derive(Debug, Display, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum GatheringType {
+    Mining = 0,
+    Quarrying = 1,
+    Logging = 2,
+    Harvesting = 3,
+    Spearfishing = 4,
+}
 
+impl GatheringType {
+    pub const ALL: [GatheringType; 5] = [
+        GatheringType::Mining,
+        GatheringType::Quarrying,
+        GatheringType::Logging,
+        GatheringType::Harvesting,
+        GatheringType::Spearfishing,
+    ];
+
+    pub fn from(gathering_type: u8) -> Result<GatheringType> {
+        GatheringType::ALL
+            .get(gathering_type as usize)
+            .copied()
+            .ok_or_else(|| eyre!("unrecognised gathering type {}", gathering_type))
+    }
+
+    /// the job that gathers from this kind of point
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            GatheringType::Mining | GatheringType::Quarrying => "MIN",
+            GatheringType::Logging | GatheringType::Harvesting => "BTN",
+            GatheringType::Spearfishing => "FSH",
+        }
+    }
+}
+
+id!(GatheringItemId);
+id!(GatheringPointId);
+id!(TerritoryTypeId);
+
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct GatheringItem {
+    pub id: GatheringItemId,
+    pub item_id: ItemId,
+    pub level: u16,
+    pub stars: u8,
+    /// only shows up once the node has been revealed (eg by a collectable rotation)
+    pub is_hidden: bool,
+}
+
+/// When a timed node is up, every Eorzean day
+#[derive(Debug, PartialEq, Eq, Constructor, Clone, Copy)]
+pub struct SpawnWindow {
+    /// Eorzean minutes after midnight
+    pub start: u16,
+    /// in Eorzean minutes
+    pub duration: u16,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GatheringPoint {
+    pub id: GatheringPointId,
+    pub gathering_type: GatheringType,
+    pub level: u16,
+    pub items: Vec<GatheringItemId>,
+    pub territory: TerritoryTypeId,
+    /// eg "Cloudtop", if the point is in a named part of the zone
+    pub place_name: Option<String>,
+    /// unspoiled/legendary/ephemeral nodes that only appear at certain times
+    pub is_limited: bool,
+    /// empty for nodes that are always up
+    pub spawn_windows: Vec<SpawnWindow>,
+    pub is_ephemeral: bool,
+}
+
+impl GatheringPoint {
+    pub fn is_timed(&self) -> bool {
+        !self.spawn_windows.is_empty()
+    }
+}
+
+/// What a collectable is worth to the collectables appraiser
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct CollectableReward {
+    pub item_id: ItemId,
+    pub currency: ItemId,
+    /// low, mid and high collectability thresholds
+    pub collectability: [u16; 3],
+    /// scrips for reaching each threshold
+    pub rewards: [u32; 3],
+}
+
+impl CollectableReward {
+    pub fn max_reward(&self) -> u32 {
+        self.rewards[2]
+    }
+}
+
+id!(LeveId);
+id!(CompanyCraftId);
+id!(WeatherId);
+id!(WeatherRateId);
+
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct Weather {
+    pub id: WeatherId,
+    pub name: String,
+}
+
+/// The chances of each weather in a zone
+#[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct WeatherRate {
+    pub id: WeatherRateId,
+    /// percent chances, which add up to 100
+    pub chances: Vec<(WeatherId, u8)>,
+}
+
+impl WeatherRate {
+    /// Picks the weather for a forecast target (0-99) from `weather::forecast_target`
+    pub fn weather_for(&self, target: u8) -> Option<WeatherId> {
+        let mut total = 0u32;
+        self.chances.iter().find_map(|&(weather, chance)| {
+            total += chance as u32;
+            ((target as u32) < total).then_some(weather)
+        })
+    }
+}
+
+/// A zone with weather, eg "Middle La Noscea"
 #[derive(Debug, PartialEq, Eq, Constructor, Clone)]
+pub struct Territory {
+    pub id: TerritoryTypeId,
+    pub name: String,
+    pub weather_rate: WeatherRateId,
+}
+
+id!(RecipeLevelId);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct RecipeLevel {
     pub rlvl: RecipeLevelId,
+    /// the job level shown in the recipe book
+    pub class_job_level: u8,
     pub progress_divider: u8,
     pub progress_modifier: u8,
     pub quality_divider: u8,
@@ -234,25 +584,91 @@ pub struct RecipeLevel {
     pub stars: u8,
 }
 
-id!(BonusStatId);
+/// The stats that food, medicine and materia can change, named after the BaseParam sheet.
+/// We only name the ones we do anything with.
+#[derive(Debug, Display, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum BaseParam {
+    GP,
+    CP,
+    Craftsmanship,
+    Control,
+    Gathering,
+    Perception,
+    #[display(fmt = "BaseParam {}", _0)]
+    Other(u16),
+}
+
+impl BaseParam {
+    pub fn from(id: u16) -> BaseParam {
+        match id {
+            10 => BaseParam::GP,
+            11 => BaseParam::CP,
+            70 => BaseParam::Craftsmanship,
+            71 => BaseParam::Control,
+            72 => BaseParam::Gathering,
+            73 => BaseParam::Perception,
+            other => BaseParam::Other(other),
+        }
+    }
+
+    pub fn is_crafting_stat(&self) -> bool {
+        matches!(
+            self,
+            BaseParam::CP | BaseParam::Craftsmanship | BaseParam::Control
+        )
+    }
+
+    pub fn is_gathering_stat(&self) -> bool {
+        matches!(
+            self,
+            BaseParam::GP | BaseParam::Gathering | BaseParam::Perception
+        )
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Constructor, Clone)]
 pub struct FoodBonus {
-    pub bonus_id: BonusStatId,
-    // TODO: intern?
-    pub name: String,
-    pub max: u8,
-    pub max_hq: u8,
-    pub value: u8,
-    pub value_hq: u8,
+    pub param: BaseParam,
+    /// if true, `value` is a percentage of the current stat (up to `max`).
+    /// Otherwise it's a flat amount
+    pub is_relative: bool,
+    pub value: i16,
+    pub max: i16,
+    pub value_hq: i16,
+    pub max_hq: i16,
+}
+
+impl FoodBonus {
+    /// How much the bonus adds to a stat that's currently `stat`
+    pub fn amount(&self, stat: u16, hq: bool) -> i16 {
+        let (value, max) = if hq {
+            (self.value_hq, self.max_hq)
+        } else {
+            (self.value, self.max)
+        };
+        if self.is_relative {
+            let percent = (i32::from(stat) * i32::from(value) / 100) as i16;
+            percent.min(max)
+        } else {
+            value
+        }
+    }
 }
 
-id!(FoodId);
 #[derive(Debug, PartialEq, Eq, Constructor, Clone)]
 pub struct Food {
-    pub food_id: FoodId,
-    // TODO: since there's only two possible values here, it'd be nice to intern these strings
-    pub item_ui_category_name: String,
+    pub item_id: ItemId,
     pub name: String,
+    pub is_medicine: bool,
     pub bonuses: Vec<FoodBonus>,
 }
+
+impl Food {
+    pub fn is_crafting_food(&self) -> bool {
+        self.bonuses.iter().any(|b| b.param.is_crafting_stat())
+    }
+
+    pub fn is_gathering_food(&self) -> bool {
+        self.bonuses.iter().any(|b| b.param.is_gathering_stat())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{embedded_data, model::Language};
+use itertools::Itertools;
+
+include!(concat!(env!("CARGO_MANIFEST_DIR"), "/search_key.rs"));
+
+/// How well a search key matched a query. Better matches sort first.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    /// the query starts at a word boundary somewhere in the name
+    WordPrefix,
+    Substring,
+    /// every word in the query is the start of some word in the name
+    /// eg "tinc str" for "Grade 8 Tincture of Strength"
+    AllWords,
+    /// close to the name (or the start of the name) with this many typos
+    Typo(usize),
+}
+
+/// The number of typos we'll put up with before a name stops being a suggestion
+fn max_typos(query: &str) -> usize {
+    match query.len() {
+        0..=4 => 0,
+        5..=7 => 1,
+        8..=15 => 2,
+        _ => 3,
+    }
+}
+
+/// Compares a folded query against a folded name. Both should have been
+/// through `search_key` already.
+pub fn match_kind(query: &str, key: &str) -> Option<MatchKind> {
+    if query.is_empty() {
+        return None;
+    }
+    if key == query {
+        return Some(MatchKind::Exact);
+    }
+    if key.starts_with(query) {
+        return Some(MatchKind::Prefix);
+    }
+    if key.contains(query) {
+        // not a prefix, so every match has something before it
+        let word_start = key
+            .match_indices(query)
+            .any(|(i, _)| key.as_bytes()[i - 1] == b' ');
+        return Some(if word_start {
+            MatchKind::WordPrefix
+        } else {
+            MatchKind::Substring
+        });
+    }
+    let words = key.split(' ').collect_vec();
+    if query
+        .split(' ')
+        .all(|q| words.iter().any(|w| w.starts_with(q)))
+    {
+        return Some(MatchKind::AllWords);
+    }
+
+    let allowed = max_typos(query);
+    if allowed == 0 {
+        return None;
+    }
+    // compare against the start of the name too, so that partially-typed names still match
+    let query_len = query.len();
+    let distance = (query_len.saturating_sub(allowed)..=query_len + allowed)
+        .filter_map(|len| key.get(..len))
+        .chain([key])
+        .map(|k| strsim::levenshtein(query, k))
+        .min()
+        .unwrap_or(usize::MAX);
+    (distance <= allowed).then_some(MatchKind::Typo(distance))
+}
+
+/// Item indices (into the embedded item list) that match `query`, best first.
+/// Names that are equally good get sorted shortest first, since longer names
+/// tend to be more obscure variants (eg "Grade 8 Tincture of Strength" before
+/// "Grade 8 Tincture of Strength (Tradeable)")
+pub fn search_item_indices(query: &str, language: Language) -> Vec<usize> {
+    let query = search_key(query);
+
+    embedded_data::item_search_keys(language)
+        .filter_map(|(key, indices)| match_kind(&query, key).map(|m| (m, key, indices)))
+        .sorted_by_key(|(m, key, _)| (*m, key.len(), *key))
+        .flat_map(|(_, _, indices)| indices.iter().copied())
+        .collect_vec()
+}
+
+/// Like `search_item_indices`, but with only one item for each distinct name
+pub fn suggest_item_indices(query: &str, language: Language, limit: usize) -> Vec<usize> {
+    let query = search_key(query);
+
+    embedded_data::item_search_keys(language)
+        .filter_map(|(key, indices)| match_kind(&query, key).map(|m| (m, key, indices)))
+        .sorted_by_key(|(m, key, _)| (*m, key.len(), *key))
+        .filter_map(|(_, _, indices)| indices.first().copied())
+        .take(limit)
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_keys_ignore_case_accents_and_punctuation() {
+        assert_eq!("uldahn ubersuit", search_key("Ul'dahn Übersuit"));
+        assert_eq!(
+            "grade 8 tincture of strength",
+            search_key("Grade 8  Tincture of Strength")
+        );
+        assert_eq!("cloud mica", search_key("Cloud-Mica "));
+    }
+
+    #[test]
+    fn better_matches_sort_first() {
+        let key = "grade 8 tincture of strength";
+        assert_eq!(Some(MatchKind::Exact), match_kind(key, key));
+        assert_eq!(Some(MatchKind::Prefix), match_kind("grade 8", key));
+        assert_eq!(Some(MatchKind::WordPrefix), match_kind("tincture", key));
+        assert_eq!(Some(MatchKind::Substring), match_kind("incture", key));
+        assert_eq!(Some(MatchKind::AllWords), match_kind("tinc str", key));
+        assert!(MatchKind::Prefix < MatchKind::Typo(1));
+    }
+
+    #[test]
+    fn tolerates_a_few_typos() {
+        let key = "grade 8 tincture of strength";
+        assert_eq!(
+            Some(MatchKind::Typo(1)),
+            match_kind("grade 8 tincure of strength", key)
+        );
+        assert_eq!(Some(MatchKind::Typo(1)), match_kind("grade 8 tincure", key));
+        assert_eq!(None, match_kind("grade 3 potion of dexterity", key));
+        // short queries need to be exact (or at least a substring)
+        assert_eq!(None, match_kind("gade", "grade"));
+    }
+}
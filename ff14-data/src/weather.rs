@@ -0,0 +1,105 @@
+//! Eorzean weather forecasting, based on
+//! https://github.com/xivapi/ffxiv-datamining/blob/master/docs/Weather.md
+//!
+//! Times here are unix timestamps in seconds. Weather changes every 8 bells
+//! (Eorzean hours), at 00:00, 08:00 and 16:00 ET.
+
+/// how long an Eorzean hour lasts in real seconds
+pub const SECONDS_PER_BELL: i64 = 175;
+/// how long each weather lasts in real seconds
+pub const PERIOD_SECONDS: i64 = 8 * SECONDS_PER_BELL;
+
+/// The start of the weather period containing `unix_seconds`
+pub fn period_start(unix_seconds: i64) -> i64 {
+    unix_seconds - unix_seconds.rem_euclid(PERIOD_SECONDS)
+}
+
+/// A number from 0-99 which picks the weather for the period containing
+/// `unix_seconds`, using the chances in a `WeatherRate`
+pub fn forecast_target(unix_seconds: i64) -> u8 {
+    let bell = unix_seconds / SECONDS_PER_BELL;
+    let increment = (bell + 8 - (bell % 8)) % 24;
+    let total_days = (unix_seconds / (24 * SECONDS_PER_BELL)) as u32;
+    let calc_base = total_days.wrapping_mul(100).wrapping_add(increment as u32);
+    let step1 = (calc_base << 11) ^ calc_base;
+    let step2 = (step1 >> 8) ^ step1;
+    (step2 % 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::WeatherLookup;
+    use crate::model::{
+        Territory, TerritoryTypeId, Weather, WeatherId, WeatherRate, WeatherRateId,
+    };
+
+    #[test]
+    fn forecast_is_the_same_for_a_whole_period() {
+        let start = period_start(1_700_000_000);
+        assert_eq!(0, start % PERIOD_SECONDS);
+        assert!(start <= 1_700_000_000 && 1_700_000_000 < start + PERIOD_SECONDS);
+
+        let target = forecast_target(start);
+        assert!(target < 100);
+        assert_eq!(target, forecast_target(start + PERIOD_SECONDS - 1));
+    }
+
+    #[test]
+    fn weather_rates_are_cumulative() {
+        let rate = WeatherRate::new(
+            WeatherRateId::new(1),
+            vec![
+                (WeatherId::new(1), 15),
+                (WeatherId::new(2), 70),
+                (WeatherId::new(3), 15),
+            ],
+        );
+        assert_eq!(Some(WeatherId::new(1)), rate.weather_for(0));
+        assert_eq!(Some(WeatherId::new(1)), rate.weather_for(14));
+        assert_eq!(Some(WeatherId::new(2)), rate.weather_for(15));
+        assert_eq!(Some(WeatherId::new(3)), rate.weather_for(99));
+    }
+
+    #[test]
+    fn forecasts_need_rates_that_cover_every_period() {
+        let territory = |rate| {
+            Territory::new(
+                TerritoryTypeId::new(1),
+                "Zone".to_string(),
+                WeatherRateId::new(rate),
+            )
+        };
+        let (always, partly, never) = (territory(1), territory(2), territory(3));
+        let lookup = WeatherLookup::new(
+            vec![always.clone(), partly.clone(), never.clone()],
+            [
+                WeatherRate::new(WeatherRateId::new(1), vec![(WeatherId::new(1), 100)]),
+                WeatherRate::new(WeatherRateId::new(2), vec![(WeatherId::new(1), 60)]),
+            ]
+            .into_iter()
+            .map(|r| (r.id, r))
+            .collect(),
+            [(
+                WeatherId::new(1),
+                Weather::new(WeatherId::new(1), "Clear Skies".to_string()),
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        let forecast = lookup
+            .forecast(&always, 1_700_000_000)
+            .unwrap()
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(forecast.len(), 3);
+        assert_eq!(forecast[1].0 - forecast[0].0, PERIOD_SECONDS);
+        // only covers 60% of the targets, or has no rates at all
+        assert!(lookup.forecast(&partly, 1_700_000_000).is_err());
+        assert!(lookup.forecast(&never, 1_700_000_000).is_err());
+        assert!(lookup
+            .find_sequence(&partly, &["Clear Skies"], 1_700_000_000)
+            .is_err());
+    }
+}
@@ -105,18 +105,26 @@
             "cosmic-weather"
             "cosmocredit-items"
             "everkeep-certs"
+            "export-data"
+            "gatherer-scrips"
             "heliometry-items"
             "leve-compare"
             "list-recipes"
             "map-compare"
             "materia-prices"
+            "meld-cost"
             "orange-scrip-items"
             "orange-scrips"
+            "patch-diff"
             "purple-scrip-items"
             "purple-scrips"
             "recipe-compare"
             "shopping"
             "specific-recipe"
+            "timed-nodes"
+            "used-in"
+            "weather-forecast"
+            "workshop-cost"
           ];
           pkg = self.packages.${system}.default;
         in
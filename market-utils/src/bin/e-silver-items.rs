@@ -1,7 +1,12 @@
-use ff14_utils::scrip_compare::print_script_sink_compare;
+use color_eyre::eyre::Result;
+use ff14_utils::{config::Config, scrip_compare::print_item_sink_compare};
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let (config, _) = Config::from_env()?;
+
     let items = [
         (40, "Occult Potion"),
         (40, "Occult Coffer"),
@@ -25,5 +30,7 @@ async fn main() {
         (1000, "Town Theme (Dawntrail) Orchestrion Roll"),
     ];
 
-    print_script_sink_compare(&items, 2000).await;
+    print_item_sink_compare(&items, 2000, &config).await?;
+
+    Ok(())
 }
@@ -1,9 +1,8 @@
 use color_eyre::eyre::Result;
-use ff14_data::{
-    lookup::{ItemLookup, RecipeLookup},
-    model::*,
+use ff14_data::lookup::{CurrencyExchangeLookup, ItemLookup, RecipeLookup, VendorLookup};
+use ff14_utils::{
+    config::Config, recipe_calculation::process_recipe_item, universalis::get_market_data_lookup,
 };
-use ff14_utils::{recipe_calculation::process_recipe_item, universalis::get_market_data_lookup};
 use itertools::Itertools;
 use thousands::Separable;
 
@@ -11,17 +10,29 @@ use thousands::Separable;
 async fn main() -> Result<()> {
     color_eyre::install()?;
 
+    let (config, _) = Config::from_env()?;
+
     let items_lookup = ItemLookup::from_embedded()?;
     let recipes_lookup = RecipeLookup::from_embedded()?;
+    let vendors = VendorLookup::from_embedded()?;
+    let exchanges = CurrencyExchangeLookup::from_embedded()?;
 
     let items = items_lookup
         .matching(|i| i.ilvl == 740 && i.name.starts_with("Ceremonial"))
-        .map(|i| {
-            (
+        .filter_map(|i| {
+            // whatever the gear turns into, which should be certificates
+            let Some(certs) = exchanges
+                .exchanges_costing(i.id)
+                .find_map(|e| e.receive.first())
+            else {
+                eprintln!("{}: no exchange found, skipping", i.name);
+                return None;
+            };
+            Some((
                 &i.name,
-                num_certs_for(i.equip_slot_category.unwrap()),
+                certs.amount,
                 recipes_lookup.recipe_for_item(i.id).unwrap(),
-            )
+            ))
         })
         .collect_vec();
 
@@ -30,7 +41,7 @@ async fn main() -> Result<()> {
         .map(|(_, _, recipe)| recipe)
         .flat_map(|r| r.relevant_item_ids(&recipes_lookup))
         .collect_vec();
-    let market_data = get_market_data_lookup(&all_ids).await?;
+    let market_data = get_market_data_lookup(&all_ids, &config).await?;
 
     let result_lines = items
         .iter()
@@ -41,6 +52,7 @@ async fn main() -> Result<()> {
                     0,
                     &recipe.result,
                     &items_lookup,
+                    &vendors,
                     &market_data,
                     &recipes_lookup,
                     true,
@@ -64,24 +76,3 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
-
-fn num_certs_for(slot: EquipSlotCategory) -> u32 {
-    match slot {
-        EquipSlotCategory::TwoHandWeapon => 17,
-        EquipSlotCategory::MainHand => 10,
-        EquipSlotCategory::OffHand => 7,
-
-        EquipSlotCategory::Body => 17,
-        EquipSlotCategory::Legs => 17,
-
-        EquipSlotCategory::Head => 11,
-        EquipSlotCategory::Gloves => 11,
-        EquipSlotCategory::Feet => 11,
-
-        EquipSlotCategory::Ears => 7,
-        EquipSlotCategory::Neck => 7,
-        EquipSlotCategory::Wrists => 7,
-        EquipSlotCategory::Ring => 7,
-        _ => panic!("EquipSlot {} not matched", slot),
-    }
-}
@@ -0,0 +1,52 @@
+use color_eyre::eyre::{eyre, Context, Result};
+use ff14_data::{
+    lookup::{GatheringLookup, ItemLookup},
+    model::Language,
+};
+use ff14_utils::{config::Config, scrip_compare::print_gathering_scrip_source_compare};
+use itertools::Itertools;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let mut currency = "Orange Gatherers' Scrip".to_string();
+    // a rough guess, including flying between nodes
+    let mut gathers_per_hour = 60;
+
+    let (config, args) = Config::from_env()?;
+    match &args[1..] {
+        [] => {}
+        [c] => currency = c.clone(),
+        [c, per_hour] => {
+            currency = c.clone();
+            gathers_per_hour = per_hour
+                .parse::<u32>()
+                .wrap_err("Failed to parse gathers per hour")?;
+        }
+        _ => return Err(eyre!("Usage: gatherer-scrips [currency] [per hour]")),
+    }
+
+    let items = ItemLookup::from_embedded()?;
+    let gathering = GatheringLookup::from_embedded()?;
+    let currency = items.find_item_by_name(&currency, Language::English)?;
+
+    let collectables = gathering
+        .gatherable_collectables()
+        .filter(|c| c.currency == currency.id)
+        .collect_vec();
+    if collectables.is_empty() {
+        return Err(eyre!("No gathered collectables reward {}", currency.name));
+    }
+
+    print_gathering_scrip_source_compare(
+        &items,
+        &gathering,
+        collectables,
+        gathers_per_hour,
+        &config,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,10 +1,16 @@
-use color_eyre::eyre::{eyre, Result};
-use ff14_data::leve::{get_relevant_leves, Leve};
+use color_eyre::eyre::{eyre, Context, Result};
+use ff14_data::{
+    leve::Leve,
+    lookup::{ItemLookup, LeveLookup},
+    model::CraftingJob,
+};
 use ff14_utils::{
+    config::Config,
     format_table::Table,
     universalis::{get_market_data_lookup, price_up_to},
 };
 use itertools::Itertools;
+use std::ops::RangeInclusive;
 use thousands::Separable;
 
 #[tokio::main]
@@ -14,29 +20,73 @@ async fn main() -> Result<()> {
     run().await
 }
 
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a String>> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .map(|i| {
+            args.get(i + 1)
+                .ok_or_else(|| eyre!("Expected a value after {}", flag))
+        })
+        .transpose()
+}
+
+/// eg "80-100" or just "90"
+fn parse_levels(levels: &str) -> Result<RangeInclusive<u8>> {
+    let (min, max) = levels.split_once('-').unwrap_or((levels, levels));
+    let parse = |l: &str| {
+        l.parse::<u8>()
+            .wrap_err_with(|| format!("Failed to parse level {}", l))
+    };
+    Ok(parse(min)?..=parse(max)?)
+}
+
 async fn run() -> Result<()> {
-    let leves = get_relevant_leves()?;
+    let (config, args) = Config::from_env()?;
+    if args.iter().any(|arg| arg == "--help") {
+        return Err(eyre!(
+            "Usage: leve-compare [--job CRP|BSM|...] [--level 80-100] [--nq]"
+        ));
+    }
+    let job = flag_value(&args, "--job")?
+        .map(|j| CraftingJob::from_abbreviation(j))
+        .transpose()?;
+    let levels = flag_value(&args, "--level")?
+        .map(|l| parse_levels(l))
+        .transpose()?
+        .unwrap_or(80..=100);
+    let hq = !args.iter().any(|arg| arg == "--nq");
+
+    let items = ItemLookup::from_embedded()?;
+    let leves = LeveLookup::from_embedded()?
+        .matching(|l| {
+            job.is_none_or(|j| l.job == j) && levels.contains(&l.level) && l.gil_reward > 0
+        })
+        .cloned()
+        .collect_vec();
 
-    // dbg!(&leves);
-    // dbg!(leves.len());
-    //
-    let ids = leves.iter().map(|l| l.item_id).collect_vec();
+    let ids = leves
+        .iter()
+        .flat_map(|l| l.items.iter().map(|i| i.item_id))
+        .unique()
+        .collect_vec();
 
-    let market_data = get_market_data_lookup(&ids).await?;
+    let market_data = get_market_data_lookup(&ids, &config).await?;
 
     let mut bottom_lines = leves
         .iter()
         .map(|leve| -> Result<(u32, u32, &Leve)> {
-            let hq_reward = leve.gil_reward * 2;
-            let md = market_data.get(&leve.item_id);
-            if md.is_none() {
-                eprintln!("No market data for {}, skipping...", leve.item_name);
-                return Err(eyre!("No market data for {}", leve.item_name));
+            let reward = leve.total_gil(hq);
+            let mut market_price = 0;
+            for item in leve.total_items() {
+                let name = &items.item_by_id(item.item_id).name;
+                let Some(md) = market_data.get(&item.item_id) else {
+                    eprintln!("No market data for {}, skipping...", name);
+                    return Err(eyre!("No market data for {}", name));
+                };
+                market_price +=
+                    price_up_to(&md.listings, item.amount, hq).map_err(|e| eyre!("{}", e))?;
             }
-            let md = md.unwrap();
-            let market_price = price_up_to(&md.listings, leve.item_count.into(), true)
-                .map_err(|e| eyre!("{}", e))?;
-            Ok((hq_reward, market_price, leve))
+            Ok((reward, market_price, leve))
         })
         .filter_map(|r| r.ok())
         .collect_vec();
@@ -56,6 +106,12 @@ async fn run() -> Result<()> {
         if profit < 0 {
             continue; // Skip negative profit lines
         }
+        let leve = line.2;
+        let turn_ins = leve
+            .total_items()
+            .iter()
+            .map(|i| format!("{} x{}", items.item_by_id(i.item_id).name, i.amount))
+            .join(", ");
         table.add_row([
             format!("{}", profit.separate_with_commas()),
             format!(
@@ -64,8 +120,12 @@ async fn run() -> Result<()> {
                 line.1.separate_with_commas()
             ),
             format!(
-                "{} ({} x{})",
-                line.2.leve_name, line.2.item_name, line.2.item_count
+                "{} {} {} ({}), {} exp",
+                leve.job.abbreviation(),
+                leve.level,
+                leve.name,
+                turn_ins,
+                leve.total_exp(hq).separate_with_commas()
             ),
         ]);
     }
@@ -1,6 +1,6 @@
 use color_eyre::eyre::Result;
 use ff14_data::{lookup::ItemLookup, model::Item};
-use ff14_utils::universalis::get_market_data_lookup;
+use ff14_utils::{config::Config, universalis::get_market_data_lookup};
 use itertools::Itertools;
 
 #[tokio::main]
@@ -11,6 +11,7 @@ async fn main() -> Result<()> {
 }
 
 async fn run() -> Result<()> {
+    let (config, _) = Config::from_env()?;
     let lookup = ItemLookup::from_embedded()?;
     let maps = lookup
         .matching(|i| i.name.starts_with("Timeworn") && i.name.ends_with("Map"))
@@ -20,7 +21,7 @@ async fn run() -> Result<()> {
     //
     let ids = maps.iter().map(|l| l.id).collect_vec();
 
-    let market_data = get_market_data_lookup(&ids).await?;
+    let market_data = get_market_data_lookup(&ids, &config).await?;
 
     let mut bottom_lines = maps
         .iter()
@@ -1,12 +1,14 @@
 use color_eyre::eyre::Result;
 use ff14_data::lookup::{ItemLookup, MateriaLookup};
-use ff14_utils::{time_utils::hm_ago_from_now, universalis};
+use ff14_utils::{config::Config, time_utils::hm_ago_from_now, universalis};
 use itertools::Itertools;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
 
+    let (config, _) = Config::from_env()?;
+
     let items = ItemLookup::from_embedded()?;
     let materia = MateriaLookup::from_embedded()?;
 
@@ -14,22 +16,30 @@ async fn main() -> Result<()> {
         .iter()
         .flat_map(|m| m.materia_levels.iter())
         .filter(|ml| ml.level >= 11)
-        .map(|ml| (ml.item_id, &items.item_by_id(ml.item_id).name))
+        .map(|ml| ml.item_id)
         .collect_vec();
 
-    let response =
-        universalis::get_market_data(&all_materia.iter().map(|m| m.0).collect_vec()).await?;
+    let response = universalis::get_market_data(&all_materia, &config).await?;
     let data = response
         .iter()
-        .map(|d| (&items.item_by_id(d.item_id).name, d))
+        .map(|d| {
+            let param = materia.for_item(d.item_id).map(|(m, _)| m.base_param);
+            (&items.item_by_id(d.item_id).name, param, d)
+        })
         .sorted_by_key(|d| d.0)
         .collect_vec();
 
-    for (name, market_data) in data {
+    for (name, param, market_data) in data {
+        let cheapest = market_data
+            .listings
+            .first()
+            .expect(&format!("price for {name}"));
         println!(
-            "{:<40} cheapest {:>7}, last updated {}",
+            "{:<40} {:<14} cheapest {:>7} on {}, last updated {}",
             format!("{name}:"),
-            market_data.listings.first().expect(&format!("price for {name}")).price_per_item,
+            param.map(|p| p.to_string()).unwrap_or_default(),
+            cheapest.price_per_item,
+            cheapest.world,
             hm_ago_from_now(market_data.last_upload_time)
         );
     }
@@ -0,0 +1,133 @@
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use ff14_data::{
+    lookup::{ItemLookup, MateriaLookup},
+    melding::{GearKind, MeldPlan},
+    model::*,
+};
+use ff14_utils::{
+    config::Config,
+    universalis::{get_market_data_lookup, price_up_to},
+};
+use itertools::Itertools;
+use std::{collections::HashMap, fs};
+use thousands::Separable;
+
+/// Estimates what melding a gearset costs, counting the materia that overmelds are
+/// expected to eat. The gearset file has one line per piece of gear:
+///
+///     <guaranteed slots>: <materia>, <materia>, ...
+///
+/// with the materia in slot order. Blank lines and lines starting with # are ignored.
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let (config, args) = Config::from_env()?;
+    let hq = !args.iter().any(|arg| arg == "--nq");
+    let kind = if args.iter().any(|arg| arg == "--battle") {
+        GearKind::Battle
+    } else {
+        GearKind::CraftingGathering
+    };
+    let positional = args[1..]
+        .iter()
+        .filter(|arg| !arg.starts_with("--"))
+        .collect_vec();
+    let path = match positional[..] {
+        [path] => path,
+        _ => return Err(eyre!("Usage: meld-cost <gearset file> [--nq] [--battle]")),
+    };
+
+    let items = ItemLookup::from_embedded()?;
+    let materia = MateriaLookup::from_embedded()?;
+
+    let gearset = fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {}", path))?;
+    let plans = gearset
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| parse_piece(line, hq, &items))
+        .collect::<Result<Vec<_>>>()?;
+
+    // expected number of each materia used up across the whole set
+    let mut expected: HashMap<ItemId, f64> = HashMap::new();
+    for plan in &plans {
+        for meld in materia.expected_melds(plan, kind)? {
+            *expected.entry(meld.item_id).or_default() += meld.expected_materia();
+        }
+    }
+    if expected.is_empty() {
+        return Err(eyre!("No materia in {}", path));
+    }
+
+    let ids = expected.keys().copied().collect_vec();
+    let market_data = get_market_data_lookup(&ids, &config).await?;
+
+    let mut total = 0.0;
+    let mut unpriced = vec![];
+    for (item_id, amount) in expected.into_iter().sorted_by(|a, b| b.1.total_cmp(&a.1)) {
+        let name = &items.item_by_id(item_id).name;
+        // price the listings we'd actually have to buy through, then spread that over what we expect to use
+        let to_buy = amount.ceil() as u32;
+        let cost = market_data
+            .get(&item_id)
+            .and_then(|md| price_up_to(&md.listings, to_buy, false).ok())
+            .map(|price| price as f64 / to_buy as f64 * amount);
+        match cost {
+            Some(cost) => {
+                total += cost;
+                println!(
+                    "{:<45} x{:>5.1}  {:>12}",
+                    name,
+                    amount,
+                    (cost.round() as u64).separate_with_commas()
+                );
+            }
+            None => {
+                unpriced.push(name);
+                println!(
+                    "{:<45} x{:>5.1}  {:>12}",
+                    name, amount, "not enough listings"
+                )
+            }
+        }
+    }
+    let total = (total.round() as u64).separate_with_commas();
+    if unpriced.is_empty() {
+        println!("Expected total: {} gil", total);
+    } else {
+        // the materia we can't price are usually the expensive ones, so don't pretend this is complete
+        println!(
+            "Expected total: at least {} gil (excluding {} unpriced materia: {})",
+            total,
+            unpriced.len(),
+            unpriced.iter().join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_piece(line: &str, hq: bool, items: &ItemLookup) -> Result<MeldPlan> {
+    let (slots, materia) = line.split_once(':').ok_or_else(|| {
+        eyre!(
+            "Expected '<guaranteed slots>: <materia>, ...', got '{}'",
+            line
+        )
+    })?;
+    let guaranteed_slots = slots
+        .trim()
+        .parse::<usize>()
+        .wrap_err_with(|| format!("Failed to parse guaranteed slots in '{}'", line))?;
+    let materia = materia
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            items
+                .find_item_by_name(name, items.display_language())
+                .map(|i| i.id)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(MeldPlan::new(guaranteed_slots, hq, materia))
+}
@@ -1,7 +1,5 @@
 use color_eyre::eyre::{eyre, Context, Result};
-use ff14_utils::scrip_compare::print_script_sink_compare;
-use itertools::Itertools;
-use std::env;
+use ff14_utils::{config::Config, scrip_compare::print_script_sink_compare};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -9,7 +7,7 @@ async fn main() -> Result<()> {
 
     let mut target_scrip_count = 4_000;
 
-    let args = env::args().collect_vec();
+    let (config, args) = Config::from_env()?;
     match &args[1..] {
         [] => {}
         [count] => {
@@ -18,35 +16,9 @@ async fn main() -> Result<()> {
         _ => return Err(eyre!("Usage: orange-scrip-items [script amount]")),
     }
 
-    let items = [
-        // crafter
-        (15, "Queso Fresco"),
-        (15, "Woolback Loin"),
-        (15, "Cassava"),
-        (15, "Splendid Mate Leaves"),
-        (15, "Aji Amarillo"),
-        (125, "Condensed Solution"),
-        (10, "Rumpless Chicken"),
-        (10, "Navel Orange"),
-        (10, "Wild Coffee Beans"),
-        (10, "Brown Cardamom"),
-        (10, "Royal Lobster"),
-        (500, "Craftsman's Command Materia XII"),
-        (500, "Craftsman's Competence Materia XII"),
-        (500, "Craftsman's Cunning Materia XII"),
-        // gatherer
-        (5, "Ghost Nipper"),
-        (5, "Red Maggots"),
-        (300, "Levinchrome Aethersand"),
-        (100, "Sungilt Aethersand"),
-        (200, "Mythloam Aethersand"),
-        (200, "Mythroot Aethersand"),
-        (200, "Mythbrine Aethersand"),
-        (1000, "Mount Token"),
-        (500, "Ghost Botanist's Wall Frames"),
-    ];
+    let currencies = ["Orange Crafters' Scrip", "Orange Gatherers' Scrip"];
 
-    print_script_sink_compare(&items, target_scrip_count).await;
+    print_script_sink_compare(&currencies, target_scrip_count, &config).await?;
 
     Ok(())
 }
@@ -1,7 +1,5 @@
 use color_eyre::eyre::{eyre, Context, Result};
-use ff14_utils::scrip_compare::print_script_sink_compare;
-use itertools::Itertools;
-use std::env;
+use ff14_utils::{config::Config, scrip_compare::print_script_sink_compare};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -9,7 +7,7 @@ async fn main() -> Result<()> {
 
     let mut target_scrip_count = 4_000;
 
-    let args = env::args().collect_vec();
+    let (config, args) = Config::from_env()?;
     match &args[1..] {
         [] => {}
         [count] => {
@@ -18,33 +16,9 @@ async fn main() -> Result<()> {
         _ => return Err(eyre!("Usage: purple-scrip-items [script amount]")),
     }
 
-    let items = [
-        (250, "Craftsman's Competence Materia XI"),
-        (250, "Craftsman's Cunning Materia XI"),
-        (250, "Craftsman's Command Materia XI"),
-        (200, "Craftsman's Competence Materia X"),
-        (200, "Craftsman's Cunning Materia X"),
-        (200, "Craftsman's Command Materia X"),
-        (200, "Craftsman's Competence Materia IX"),
-        (200, "Craftsman's Cunning Materia IX"),
-        (200, "Craftsman's Command Materia IX"),
-        (200, "Craftsman's Competence Materia VIII"),
-        (200, "Craftsman's Cunning Materia VIII"),
-        (200, "Craftsman's Command Materia VIII"),
-        (200, "Craftsman's Competence Materia VII"),
-        (200, "Craftsman's Cunning Materia VII"),
-        (200, "Craftsman's Command Materia VII"),
-        (200, "Craftsman's Competence Materia VI"),
-        (200, "Craftsman's Cunning Materia VI"),
-        (200, "Craftsman's Command Materia VI"),
-        (200, "Craftsman's Competence Materia V"),
-        (200, "Craftsman's Cunning Materia V"),
-        (200, "Craftsman's Command Materia V"),
-        (500, "Mason's Abrasive"),
-        (15, "Crafter's Delineation"),
-    ];
+    let currencies = ["Purple Crafters' Scrip"];
 
-    print_script_sink_compare(&items, target_scrip_count).await;
+    print_script_sink_compare(&currencies, target_scrip_count, &config).await?;
 
     Ok(())
 }
@@ -1,8 +1,10 @@
 use color_eyre::eyre::{eyre, Context, Result};
-use ff14_data::lookup::{ItemLookup, RecipeLookup};
-use ff14_utils::scrip_compare::print_scrip_source_compare;
+use ff14_data::{
+    lookup::{ItemLookup, RecipeLookup},
+    model::Language,
+};
+use ff14_utils::{config::Config, scrip_compare::print_scrip_source_compare};
 use itertools::Itertools;
-use std::env;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -10,7 +12,7 @@ async fn main() -> Result<()> {
 
     let mut target_scrip_count = 4_000;
 
-    let args = env::args().collect_vec();
+    let (config, args) = Config::from_env()?;
     match &args[1..] {
         [] => {}
         [count] => {
@@ -21,18 +23,25 @@ async fn main() -> Result<()> {
 
     let items = ItemLookup::from_embedded()?;
     let recipes_lookup = RecipeLookup::from_embedded()?;
-
-    let l89_99_collectables = items
-        .matching(|i| (i.ilvl >= 548 && i.ilvl <= 685) && i.name.starts_with("Rarefied"))
-        .collect_vec();
-
-    let recipes = l89_99_collectables
-        .iter()
-        // only include items that have a recipe (ie skip gathering collectables)
-        .filter_map(|i| recipes_lookup.recipe_for_item(i.id))
+    let currency = items.find_item_by_name("Purple Crafters' Scrip", Language::English)?;
+
+    // only crafted collectables (gatherer-scrips covers the gathered ones)
+    let recipes = recipes_lookup
+        .matching(|r| {
+            (89..=99).contains(&r.rlvl.class_job_level)
+                && items.item_by_id(r.result.item_id).is_collectable
+        })
         .collect_vec();
 
-    print_scrip_source_compare(&items, &recipes_lookup, recipes, target_scrip_count).await?;
+    print_scrip_source_compare(
+        &items,
+        &recipes_lookup,
+        recipes,
+        currency.id,
+        target_scrip_count,
+        &config,
+    )
+    .await?;
 
     Ok(())
 }
@@ -1,35 +1,44 @@
-use color_eyre::eyre::{eyre, Result};
+use color_eyre::eyre::{eyre, Report, Result};
 use ff14_data::{
-    lookup::{ItemLookup, RecipeLookup},
+    lookup::{ItemLookup, RecipeLookup, VendorLookup},
     model::*,
 };
 use ff14_utils::{
+    config::Config,
     recipe_calculation::{print_line_item, process_recipe_item},
     universalis::get_market_data_lookup,
 };
 use itertools::Itertools;
-use std::env;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
 
+    let (config, args) = Config::from_env()?;
     let items = ItemLookup::from_embedded()?;
     let recipes_lookup = RecipeLookup::from_embedded()?;
+    let vendors = VendorLookup::from_embedded()?;
 
-    let recipes = choose_recipes_from_args(&items, &recipes_lookup)?;
+    let recipes = choose_recipes_from_args(&args, &items, &recipes_lookup)?;
 
     let all_ids = recipes
         .iter()
         .flat_map(|r| r.relevant_item_ids(&recipes_lookup))
         .collect_vec();
-    let market_data = get_market_data_lookup(&all_ids).await?;
+    let market_data = get_market_data_lookup(&all_ids, &config).await?;
 
     let mut bottom_lines = recipes
         .iter()
         .map(|r| {
-            let (_, results) =
-                process_recipe_item(0, &r.result, &items, &market_data, &recipes_lookup, true);
+            let (_, results) = process_recipe_item(
+                0,
+                &r.result,
+                &items,
+                &vendors,
+                &market_data,
+                &recipes_lookup,
+                true,
+            );
             results.into_iter().last().unwrap()
         })
         .collect_vec();
@@ -44,10 +53,10 @@ async fn main() -> Result<()> {
 }
 
 fn choose_recipes_from_args<'a>(
+    args: &[String],
     items: &ItemLookup,
     recipes: &'a RecipeLookup,
 ) -> Result<Vec<&'a Recipe>> {
-    let args = env::args().collect_vec();
     let results;
 
     let look_up_recipe = |name| {
@@ -60,9 +69,31 @@ fn choose_recipes_from_args<'a>(
     match &args[1..] {
         [name] => {
             results = look_up_recipe(name);
+            if results.is_empty() {
+                return Err(no_recipes_error(items, recipes, name));
+            }
         }
         _ => return Err(eyre!("Usage: recipe-compare [substr]")),
     }
 
     Ok(results)
 }
+
+fn no_recipes_error(items: &ItemLookup, recipes: &RecipeLookup, name: &str) -> Report {
+    let suggestions = items
+        .search(name, Language::English)
+        .filter(|i| recipes.recipe_for_item(i.id).is_some())
+        .map(|i| format!("'{}'", i.name))
+        .unique()
+        .take(5)
+        .join(", ");
+    if suggestions.is_empty() {
+        eyre!("No recipes found for items containing '{}'", name)
+    } else {
+        eyre!(
+            "No recipes found for items containing '{}'. Did you mean: {}?",
+            name,
+            suggestions
+        )
+    }
+}
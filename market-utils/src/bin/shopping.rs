@@ -1,12 +1,38 @@
-use ff14_data::lookup::ItemLookup;
-use ff14_utils::format_table::Table;
-use ff14_utils::universalis::get_market_data_lookup;
+use ff14_data::{lookup::ItemLookup, model::Language};
+use ff14_utils::{
+    config::Config,
+    format_table::Table,
+    purchase_planner::{plan_purchases, Purchase},
+    universalis::get_market_data_lookup,
+};
 use itertools::Itertools;
 use std::io::{self, BufRead};
 use thousands::Separable;
 
 #[tokio::main]
 async fn main() {
+    let (config, args) = match Config::from_env() {
+        Ok(config_and_args) => config_and_args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let language = match &args[1..] {
+        [] => Language::English,
+        [flag, lang] if flag == "--lang" => match lang.parse() {
+            Ok(language) => language,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        },
+        _ => {
+            eprintln!("Usage: shopping [--lang en|ja|de|fr] [--world <world>|--dc <data center>] < shopping-list.txt");
+            return;
+        }
+    };
+
     let stdin = io::stdin();
     let handle = stdin.lock();
 
@@ -37,44 +63,48 @@ async fn main() {
     }
 
     // Resolve item names to ItemId
-    let item_lookup = ItemLookup::from_embedded().unwrap();
+    let item_lookup = ItemLookup::from_embedded()
+        .unwrap()
+        .with_display_language(language);
     let mut resolved_items = Vec::new();
     for (name, quantity) in &shopping_list {
-        if let Some(item) = item_lookup.item_by_name_opt(name) {
-            resolved_items.push((item.id, *quantity));
-        } else {
-            eprintln!("Warning: Item '{}' not found in lookup", name);
+        match item_lookup.find_item_by_name(name, language) {
+            Ok(item) => resolved_items.push((item.id, *quantity)),
+            Err(e) => eprintln!("Warning: {}, skipping", e),
         }
     }
 
     let item_ids = resolved_items.iter().map(|(id, _)| *id).collect_vec();
 
     eprintln!("Fetching market data for {} items...", item_ids.len());
-    let market_data = get_market_data_lookup(&item_ids).await.unwrap();
+    let market_data = get_market_data_lookup(&item_ids, &config).await.unwrap();
 
-    let mut total_cost = 0;
-    let mut table = Table::<String, 3>::new();
+    let plan = plan_purchases(&resolved_items, &market_data, &config.world);
+
+    let mut table = Table::<String, 4>::new();
     table.add_row([
         // TODO: can we make this more ergonomic?
         "Item Name".to_string(),
         "Quantity".to_string(),
         "Cost (gil)".to_string(),
+        "World".to_string(),
     ]);
     table.add_separator();
 
-    for (item_id, quantity) in &resolved_items {
-        let name = &item_lookup.item_by_id(*item_id).name;
-        let cost = market_data
-            .get(item_id)
-            .and_then(|data| data.listings.first().map(|listing| listing.price_per_item))
-            .unwrap_or(0) as i64
-            * *quantity as i64;
-
-        total_cost += cost;
-        let formatted_quantity = quantity.separate_with_commas();
-        let formatted_cost = cost.separate_with_commas();
+    for item in &plan.items {
+        let name = item_lookup.item_by_id(item.item_id).name_in(language);
+        let formatted_quantity = item.amount.separate_with_commas();
+        let formatted_cost = match item.cost() {
+            Some(cost) => cost.separate_with_commas(),
+            None => "not enough listings".to_string(),
+        };
 
-        table.add_row([name.to_owned(), formatted_quantity, formatted_cost]);
+        table.add_row([
+            name.to_owned(),
+            formatted_quantity,
+            formatted_cost,
+            item.worlds().join(", "),
+        ]);
     }
 
     table.add_separator();
@@ -82,8 +112,41 @@ async fn main() {
     table.add_row([
         "Total".to_string(),
         "".to_string(),
-        total_cost.separate_with_commas(),
+        plan.total().separate_with_commas(),
+        "".to_string(),
     ]);
 
     table.print();
+
+    // only worth planning a trip if we'd leave the home world
+    if plan.visits.iter().all(|(world, _)| *world == config.world) {
+        return;
+    }
+
+    println!();
+    for (world, purchases) in &plan.visits {
+        let spent: u64 = purchases.iter().map(Purchase::cost).sum();
+        println!("{} ({} gil):", world, spent.separate_with_commas());
+        for purchase in purchases {
+            println!(
+                "  {} {} at {}",
+                purchase.amount.separate_with_commas(),
+                item_lookup.item_by_id(purchase.item_id).name_in(language),
+                purchase.listing.price_per_item.separate_with_commas()
+            );
+        }
+    }
+    match plan.saved_versus_home_world() {
+        Some(saved) => println!(
+            "Saves {} gil versus buying everything on {}",
+            saved.separate_with_commas(),
+            config.world
+        ),
+        None => eprintln!(
+            "Warning: {} doesn't have enough listings of anything to compare against. \
+            Is it in {}? Set your home world with --world or in ff14-utils.toml",
+            config.world,
+            config.market()
+        ),
+    }
 }
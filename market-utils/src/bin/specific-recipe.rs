@@ -1,79 +1,208 @@
 use color_eyre::eyre::{eyre, Context, Result};
 use ff14_data::{
-    lookup::{ItemLookup, RecipeLookup},
+    lookup::{ItemLookup, RecipeLookup, VendorLookup},
     model::*,
 };
 use ff14_utils::{
-    recipe_calculation::{
-        match_recipe_to_output_count, print_recipe_calculation, process_recipe_item,
-    },
+    config::Config,
+    recipe_calculation::{match_recipe_to_output_count, print_recipe_calculation, process_recipe},
     universalis::get_market_data_lookup,
 };
 use itertools::Itertools;
-use std::env;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
 
-    let items = ItemLookup::from_embedded()?;
+    let (config, args) = Config::from_env()?;
+    let args = parse_args(&args)?;
+
+    // names are both read and printed in the chosen language
+    let items = ItemLookup::from_embedded()?.with_display_language(args.language);
     let recipes = RecipeLookup::from_embedded()?;
+    let vendors = VendorLookup::from_embedded()?;
 
-    let (recipe, require_hq) = choose_recipe_from_args(&items, &recipes)?;
+    let result_recipe = pick_recipe(&items, &recipes, args.name, args.recipe_id)?;
+    let recipe = match_recipe_to_output_count(args.count, result_recipe);
 
     let all_ids = recipe.relevant_item_ids(&recipes).collect_vec();
-    let market_data = get_market_data_lookup(&all_ids).await?;
+    let market_data = get_market_data_lookup(&all_ids, &config).await?;
 
-    let (_, results) = process_recipe_item(
+    // the chosen recipe, even if it isn't the first one for the result
+    let (_, results) = process_recipe(
         0,
-        &recipe.result,
+        &recipe,
         &items,
+        &vendors,
         &market_data,
         &recipes,
-        require_hq,
+        args.require_hq,
     );
     print_recipe_calculation(results);
 
     Ok(())
 }
 
-fn choose_recipe_from_args(items: &ItemLookup, recipes: &RecipeLookup) -> Result<(Recipe, bool)> {
-    let args = env::args().collect_vec();
-    let result_recipe;
-    let result_count;
-
-    let look_up_recipe = |name| {
-        items
-            .item_by_name_opt(name)
-            .and_then(|i| recipes.recipe_for_item(i.id))
-            .ok_or_else(|| eyre!("Could not find recipe matching item '{}'", name))
-    };
+/// What we were asked for on the command line, before looking anything up
+#[derive(Debug, PartialEq, Eq)]
+struct RecipeArgs<'a> {
+    name: &'a str,
+    count: u32,
+    recipe_id: Option<RecipeId>,
+    require_hq: bool,
+    language: Language,
+}
 
+fn parse_args(args: &[String]) -> Result<RecipeArgs<'_>> {
+    let mut recipe_id = None;
     let mut require_hq = true;
+    let mut language = Language::default();
 
-    match &args[1..] {
-        [name] => {
-            result_recipe = look_up_recipe(name)?;
-            result_count = 1;
-        }
-        [name, count] => {
-            result_recipe = look_up_recipe(name)?;
-            result_count = count.parse::<u32>().wrap_err("Failed to parse count")?;
-        }
-        [name, count, ..] => {
-            result_recipe = look_up_recipe(name)?;
-            result_count = count.parse::<u32>().wrap_err("Failed to parse count")?;
-            if args.iter().any(|arg| arg == "--no-require-hq") {
-                require_hq = false;
+    // flags (and their values) can go anywhere, so take them out before matching the rest
+    let mut positional = vec![];
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--recipe-id" => {
+                let id = rest
+                    .next()
+                    .ok_or_else(|| eyre!("Expected a recipe id after --recipe-id"))?;
+                recipe_id = Some(RecipeId::try_from(id)?);
+            }
+            "--lang" => {
+                language = rest
+                    .next()
+                    .ok_or_else(|| eyre!("Expected a language (en, ja, de or fr) after --lang"))?
+                    .parse()?;
             }
+            "--no-require-hq" => require_hq = false,
+            _ => positional.push(arg.as_str()),
         }
+    }
+
+    let (name, count) = match positional[..] {
+        [name] => (name, 1),
+        [name, count] => (
+            name,
+            count.parse::<u32>().wrap_err("Failed to parse count")?,
+        ),
         _ => {
             return Err(eyre!(
-                "Usage: specific-recipe <name> [amount] [--no-require-hq]"
+                "Usage: specific-recipe <name> [amount] [--no-require-hq] [--recipe-id <id>] [--lang <en|ja|de|fr>]"
             ))
         }
+    };
+
+    Ok(RecipeArgs {
+        name,
+        count,
+        recipe_id,
+        require_hq,
+        language,
+    })
+}
+
+/// Some items can be made by several recipes (eg by different jobs). If we haven't
+/// been told which one to use, we take the first and list the alternatives.
+fn pick_recipe<'a>(
+    items: &ItemLookup,
+    recipes: &'a RecipeLookup,
+    name: &str,
+    recipe_id: Option<RecipeId>,
+) -> Result<&'a Recipe> {
+    let item = items.find_item_by_name(name, items.display_language())?;
+    let candidates = recipes.recipes_for_item(item.id).collect_vec();
+
+    if let Some(recipe_id) = recipe_id {
+        return candidates
+            .into_iter()
+            .find(|r| r.id == recipe_id)
+            .ok_or_else(|| eyre!("Recipe {} does not make '{}'", recipe_id, name));
+    }
+
+    let first = *candidates
+        .first()
+        .ok_or_else(|| eyre!("Could not find recipe matching item '{}'", name))?;
+
+    if candidates.len() > 1 {
+        eprintln!(
+            "{} recipes make {}, using {} (choose another with --recipe-id):",
+            candidates.len(),
+            item.name_in(items.display_language()),
+            first.id
+        );
+        for recipe in &candidates {
+            eprintln!(
+                "  {} ({}): {}",
+                recipe.id,
+                recipe.job.abbreviation(),
+                describe_ingredients(items, recipe)
+            );
+        }
+    }
+
+    Ok(first)
+}
+
+fn describe_ingredients(items: &ItemLookup, recipe: &Recipe) -> String {
+    recipe
+        .ingredients
+        .iter()
+        .map(|ri| {
+            format!(
+                "{} {}",
+                ri.amount,
+                items.display_name(items.item_by_id(ri.item_id), ri.amount)
+            )
+        })
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn recipe_id_doesnt_need_an_amount() {
+        let args = args(&["specific-recipe", "Grade 8 Tincture", "--recipe-id", "35"]);
+        assert_eq!(
+            parse_args(&args).unwrap(),
+            RecipeArgs {
+                name: "Grade 8 Tincture",
+                count: 1,
+                recipe_id: Some(RecipeId::new(35)),
+                require_hq: true,
+                language: Language::English,
+            }
+        );
     }
 
-    let recipe = match_recipe_to_output_count(result_count, result_recipe);
-    Ok((recipe, require_hq))
+    #[test]
+    fn flags_can_go_between_positional_args() {
+        let args = args(&[
+            "specific-recipe",
+            "Ingot",
+            "--no-require-hq",
+            "3",
+            "--lang",
+            "de",
+        ]);
+        assert_eq!(
+            parse_args(&args).unwrap(),
+            RecipeArgs {
+                name: "Ingot",
+                count: 3,
+                recipe_id: None,
+                require_hq: false,
+                language: Language::German,
+            }
+        );
+        assert!(parse_args(&args[..3]).is_ok());
+        assert!(parse_args(&args[..5]).is_err());
+        assert!(parse_args(&["specific-recipe".to_string()]).is_err());
+    }
 }
@@ -0,0 +1,93 @@
+use color_eyre::eyre::{eyre, Result};
+use ff14_data::{
+    lookup::{GatheringLookup, ItemLookup, WeatherLookup},
+    model::Language,
+};
+use ff14_utils::{
+    gathering_planner::{plan_route, upcoming_windows, NodeWindow},
+    time_utils::{local_hm, EorzeaTime},
+};
+use itertools::Itertools;
+use std::env;
+
+/// roughly how long it takes to gather from a node, in Eorzean minutes (about 2.5 real minutes)
+const GATHER_MINUTES: i64 = 50;
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = env::args().collect_vec();
+    if args.len() < 2 {
+        return Err(eyre!("Usage: timed-nodes <item name>..."));
+    }
+
+    let items = ItemLookup::from_embedded()?;
+    let gathering = GatheringLookup::from_embedded()?;
+    let weathers = WeatherLookup::from_embedded()?;
+
+    let wanted = args[1..]
+        .iter()
+        .map(|name| items.find_item_by_name(name, Language::English))
+        .collect::<Result<Vec<_>>>()?;
+    for item in &wanted {
+        if !gathering.points_for(item.id).any(|p| p.is_timed()) {
+            println!("{} doesn't come from a timed node", item.name);
+        }
+    }
+
+    let now = EorzeaTime::now();
+    // nodes come back every Eorzean day, so that's as far as we need to look
+    let windows = upcoming_windows(&wanted, &gathering, now, now.add_minutes(24 * 60));
+    if windows.is_empty() {
+        return Ok(());
+    }
+
+    let describe = |w: &NodeWindow| {
+        let zone = weathers
+            .territory(w.point.territory)
+            .map(|t| t.name.as_str())
+            .unwrap_or("?");
+        let place = w
+            .point
+            .place_name
+            .as_ref()
+            .map(|p| format!(" ({})", p))
+            .unwrap_or_default();
+        let kind = if w.point.is_ephemeral {
+            " [ephemeral]"
+        } else {
+            ""
+        };
+        format!(
+            "{}-{} ({}) {} - {} {}{}{}",
+            local_hm(w.start.to_utc()),
+            local_hm(w.end.to_utc()),
+            w.start,
+            w.item.name,
+            w.point.gathering_type.abbreviation(),
+            zone,
+            place,
+            kind
+        )
+    };
+
+    println!("Upcoming windows ({} now):", now);
+    for w in &windows {
+        println!("  {}", describe(w));
+    }
+
+    let route = plan_route(&windows, now, GATHER_MINUTES);
+    println!();
+    println!("Route:");
+    for w in &route {
+        println!("  {}", describe(w));
+    }
+
+    println!();
+    // macros can only be 15 lines long
+    for w in route.iter().take(15) {
+        println!("{}", w.start.alarm(&w.item.name, 1));
+    }
+
+    Ok(())
+}
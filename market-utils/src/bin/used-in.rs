@@ -0,0 +1,55 @@
+use color_eyre::eyre::{eyre, Result};
+use ff14_data::lookup::{ItemLookup, RecipeLookup, VendorLookup};
+use ff14_utils::{
+    config::Config,
+    recipe_calculation::{print_line_item, process_recipe},
+    universalis::get_market_data_lookup,
+};
+use itertools::Itertools;
+
+/// Lists the recipes that use an item, most profitable last, to help decide
+/// what to do with materials sitting in retainers
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let items = ItemLookup::from_embedded()?;
+    let recipes_lookup = RecipeLookup::from_embedded()?;
+    let vendors = VendorLookup::from_embedded()?;
+
+    let (config, args) = Config::from_env()?;
+    let name = match &args[1..] {
+        [name] => name,
+        _ => return Err(eyre!("Usage: used-in <item name>")),
+    };
+    let item = items.find_item_by_name(name, items.display_language())?;
+
+    let recipes = recipes_lookup.recipes_using(item.id).collect_vec();
+    if recipes.is_empty() {
+        return Err(eyre!("No recipes use '{}'", item.name));
+    }
+
+    let all_ids = recipes
+        .iter()
+        .flat_map(|r| r.relevant_item_ids(&recipes_lookup))
+        .collect_vec();
+    let market_data = get_market_data_lookup(&all_ids, &config).await?;
+
+    let mut bottom_lines = recipes
+        .iter()
+        .map(|r| {
+            let (_, results) =
+                process_recipe(0, r, &items, &vendors, &market_data, &recipes_lookup, true);
+            results.into_iter().last().unwrap()
+        })
+        .collect_vec();
+
+    bottom_lines.sort_by_key(|l| l.crafting_profit);
+
+    println!("{} recipes use {}:", bottom_lines.len(), item.name);
+    for line in bottom_lines {
+        print_line_item(&line);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use chrono::Utc;
+use color_eyre::eyre::{eyre, Result};
+use ff14_data::{lookup::WeatherLookup, weather};
+use ff14_utils::{
+    time_utils::local_hm,
+    weather_forecast::{print_current_weather, print_upcoming},
+};
+use itertools::Itertools;
+use std::env;
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = env::args().collect_vec();
+    let (zone, sequence) = match &args[1..] {
+        [zone, sequence @ ..] => (zone, sequence.iter().map(|s| s.as_str()).collect_vec()),
+        _ => {
+            return Err(eyre!(
+                "Usage: weather-forecast <zone> [weather...]\n\
+                eg weather-forecast \"Middle La Noscea\" Rain \"Fair Skies\" for fair skies straight after rain"
+            ))
+        }
+    };
+
+    let weathers = WeatherLookup::from_embedded()?;
+    let territory = weathers.territory_by_name(zone)?;
+
+    let now = Utc::now();
+    println!("{} at {}", territory.name, local_hm(now));
+    print_current_weather(&weathers, territory, now);
+    println!();
+
+    if sequence.is_empty() {
+        for (start, w) in weathers
+            .forecast(territory, now.timestamp() + weather::PERIOD_SECONDS)?
+            .take(8)
+        {
+            let start = chrono::DateTime::from_timestamp(start, 0).expect("timestamp in range");
+            println!("{}: {}", local_hm(start), w.name);
+        }
+        return Ok(());
+    }
+
+    let name = sequence.join(" -> ");
+    let events = weathers
+        .find_sequence(
+            territory,
+            &sequence,
+            now.timestamp() + weather::PERIOD_SECONDS,
+        )?
+        .map(|start| (start, name.clone()))
+        .take(3);
+    print_upcoming(events, now);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use color_eyre::eyre::{eyre, Result};
+use ff14_data::lookup::{CompanyCraftLookup, ItemLookup, RecipeLookup, VendorLookup};
+use ff14_utils::{
+    config::Config,
+    recipe_calculation::{print_recipe_calculation, process_recipe_item},
+    universalis::get_market_data_lookup,
+};
+use itertools::Itertools;
+use thousands::Separable;
+
+/// Lists what a company workshop project (eg a submarine part) needs in each phase,
+/// then prices the whole lot, including crafting any intermediates
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let (config, args) = Config::from_env()?;
+    let name = match &args[1..] {
+        [name] => name,
+        _ => return Err(eyre!("Usage: workshop-cost <project name>")),
+    };
+
+    let items = ItemLookup::from_embedded()?;
+    let recipes = RecipeLookup::from_embedded()?;
+    let vendors = VendorLookup::from_embedded()?;
+    let crafts = CompanyCraftLookup::from_embedded()?;
+
+    let item = items.find_item_by_name(name, items.display_language())?;
+    let craft = crafts
+        .for_result(item.id)
+        .ok_or_else(|| eyre!("{} isn't built in the company workshop", item.name))?;
+
+    let mut part = None;
+    let mut phase = 0;
+    for p in &craft.phases {
+        if part != Some(p.part) {
+            part = Some(p.part);
+            phase = 0;
+        }
+        phase += 1;
+        println!("Part {}, phase {}:", p.part + 1, phase);
+        for ri in &p.items {
+            let i = items.item_by_id(ri.item_id);
+            println!("  {}x {}", ri.amount, items.display_name(i, ri.amount));
+        }
+    }
+    println!();
+
+    let materials = craft.total_materials();
+    let all_ids = materials
+        .iter()
+        .flat_map(|ri| ri.relevant_item_ids(&recipes))
+        .unique()
+        .collect_vec();
+    let market_data = get_market_data_lookup(&all_ids, &config).await?;
+
+    let mut total: u64 = 0;
+    let mut unpriced = vec![];
+    for ri in &materials {
+        // the workshop takes NQ supplies
+        let (price, lines) =
+            process_recipe_item(0, ri, &items, &vendors, &market_data, &recipes, false);
+        print_recipe_calculation(lines);
+        if price == u32::MAX {
+            let name = items.display_name(items.item_by_id(ri.item_id), ri.amount);
+            unpriced.push(format!("{} {}", ri.amount, name));
+        } else {
+            total += u64::from(price);
+        }
+    }
+    let total = total.separate_with_commas();
+    if unpriced.is_empty() {
+        println!("Total for {}: {} gil", item.name, total);
+    } else {
+        println!(
+            "Total for {}: at least {} gil (excluding {} unpriced materials: {})",
+            item.name,
+            total,
+            unpriced.len(),
+            unpriced.iter().join(", ")
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+use std::{env, fs, path::Path};
+
+/// Read from the working directory, like the crafting simulator's jobs.toml
+pub const CONFIG_PATH: &str = "./ff14-utils.toml";
+
+/// Which market boards to look at, from ff14-utils.toml and the command line, eg
+///
+/// ```toml
+/// world = "Alpha"
+/// dc = "Light"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// our home world
+    #[serde(default = "default_world")]
+    pub world: String,
+    /// if set, we look at every world in the data center rather than just `world`
+    pub dc: Option<String>,
+    /// or every world in the region
+    pub region: Option<String>,
+}
+
+fn default_world() -> String {
+    "Alpha".to_string()
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            world: default_world(),
+            dc: None,
+            region: None,
+        }
+    }
+}
+
+impl Config {
+    /// The defaults if there's no config file
+    pub fn read(path: &Path) -> Result<Config> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let contents =
+            fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {:?}", path))?;
+        toml::from_str(&contents).wrap_err_with(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// The config file with any `--world`, `--dc` or `--region` flags applied, plus the
+    /// rest of the arguments (including the program name) for the binary to match on
+    pub fn from_env() -> Result<(Config, Vec<String>)> {
+        Config::read(Path::new(CONFIG_PATH))?.with_args(env::args().collect())
+    }
+
+    /// `--world` means just that world, even if the config file asks for a data center
+    pub fn with_args(mut self, args: Vec<String>) -> Result<(Config, Vec<String>)> {
+        let mut rest = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let flag = arg.as_str();
+            if !matches!(flag, "--world" | "--dc" | "--region") {
+                rest.push(arg);
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| eyre!("Expected a name after {}", flag))?;
+            match flag {
+                "--world" => {
+                    self.world = value;
+                    self.dc = None;
+                    self.region = None;
+                }
+                "--dc" => {
+                    self.dc = Some(value);
+                    self.region = None;
+                }
+                _ => self.region = Some(value),
+            }
+        }
+        Ok((self, rest))
+    }
+
+    /// What to ask Universalis for: it takes a world, data center or region name in the
+    /// same place, so we use the broadest one we've been given
+    pub fn market(&self) -> &str {
+        self.region
+            .as_deref()
+            .or(self.dc.as_deref())
+            .unwrap_or(&self.world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_override_the_config_file_and_get_removed() {
+        let config: Config = toml::from_str("world = \"Phoenix\"\ndc = \"Light\"").unwrap();
+        assert_eq!(config.market(), "Light");
+
+        let (config, rest) = config
+            .with_args(args(&["shopping", "--world", "Odin", "--lang", "de"]))
+            .unwrap();
+        assert_eq!(config.market(), "Odin");
+        assert_eq!(rest, args(&["shopping", "--lang", "de"]));
+
+        let (config, _) = config.with_args(args(&["x", "--dc", "Chaos"])).unwrap();
+        assert_eq!((config.world.as_str(), config.market()), ("Odin", "Chaos"));
+        assert!(Config::default().with_args(args(&["x", "--dc"])).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::time_utils::EorzeaTime;
+use ff14_data::{
+    lookup::GatheringLookup,
+    model::{GatheringPoint, Item, SpawnWindow},
+};
+use itertools::Itertools;
+
+/// One time that a timed node is up
+#[derive(Debug, Clone)]
+pub struct NodeWindow<'a> {
+    pub item: &'a Item,
+    pub point: &'a GatheringPoint,
+    pub start: EorzeaTime,
+    pub end: EorzeaTime,
+}
+
+/// (start, end) of each time `window` opens, starting with the one that's open at `now`, if any
+pub fn occurrences(
+    window: SpawnWindow,
+    now: EorzeaTime,
+) -> impl Iterator<Item = (EorzeaTime, EorzeaTime)> {
+    // start from yesterday in case a window from then runs past midnight
+    (now.day() - 1..)
+        .map(move |day| {
+            let start = EorzeaTime::from_clock(day, 0, 0).add_minutes(i64::from(window.start));
+            (start, start.add_minutes(i64::from(window.duration)))
+        })
+        .filter(move |(_, end)| *end > now)
+}
+
+/// Every window for the timed nodes that have `items`, between `now` and `until`, soonest first
+pub fn upcoming_windows<'a>(
+    items: &[&'a Item],
+    gathering: &'a GatheringLookup,
+    now: EorzeaTime,
+    until: EorzeaTime,
+) -> Vec<NodeWindow<'a>> {
+    items
+        .iter()
+        .flat_map(|&item| {
+            gathering
+                .points_for(item.id)
+                .filter(|p| p.is_timed())
+                .flat_map(move |point| {
+                    point.spawn_windows.iter().flat_map(move |&w| {
+                        occurrences(w, now)
+                            .take_while(move |(start, _)| *start < until)
+                            .map(move |(start, end)| NodeWindow {
+                                item,
+                                point,
+                                start,
+                                end,
+                            })
+                    })
+                })
+        })
+        .sorted_by_key(|w| (w.start, w.end))
+        .collect_vec()
+}
+
+/// Greedily strings windows together: from wherever we are, go to whichever node
+/// (for an item we don't have yet) we can get to soonest, and assume gathering
+/// from it takes `gather_minutes` Eorzean minutes
+pub fn plan_route<'a, 'b>(
+    windows: &'b [NodeWindow<'a>],
+    now: EorzeaTime,
+    gather_minutes: i64,
+) -> Vec<&'b NodeWindow<'a>> {
+    let mut route = Vec::new();
+    let mut time = now;
+
+    loop {
+        let next = windows
+            .iter()
+            .filter(|w| !route.iter().any(|r: &&NodeWindow| r.item.id == w.item.id))
+            // there has to be time to gather before the node disappears
+            .filter(|w| w.start.max(time).add_minutes(gather_minutes) <= w.end)
+            .min_by_key(|w| (w.start.max(time), w.end));
+        let Some(next) = next else {
+            break;
+        };
+
+        time = next.start.max(time).add_minutes(gather_minutes);
+        route.push(next);
+    }
+
+    route
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_window_we_are_in_and_the_ones_after() {
+        let window = SpawnWindow::new(22 * 60, 4 * 60);
+        let now = EorzeaTime::from_clock(10, 1, 0);
+
+        let found = occurrences(window, now).take(2).collect_vec();
+        assert_eq!(
+            vec![
+                (
+                    EorzeaTime::from_clock(9, 22, 0),
+                    EorzeaTime::from_clock(10, 2, 0)
+                ),
+                (
+                    EorzeaTime::from_clock(10, 22, 0),
+                    EorzeaTime::from_clock(11, 2, 0)
+                ),
+            ],
+            found
+        );
+    }
+}
@@ -5,8 +5,12 @@
 #![allow(clippy::missing_panics_doc)]
 #![allow(clippy::module_name_repetitions)]
 
+pub mod config;
 pub mod format_table;
+pub mod gathering_planner;
+pub mod purchase_planner;
 pub mod recipe_calculation;
 pub mod scrip_compare;
 pub mod time_utils;
 pub mod universalis;
+pub mod weather_forecast;
@@ -0,0 +1,221 @@
+use crate::universalis::{listings_up_to, ItemMarketData, ItemMarketListing};
+use ff14_data::model::ItemId;
+use itertools::Itertools;
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Buying `amount` from one listing
+#[derive(Debug, Clone)]
+pub struct Purchase<'a> {
+    pub item_id: ItemId,
+    pub listing: &'a ItemMarketListing,
+    pub amount: u32,
+}
+
+impl Purchase<'_> {
+    pub fn cost(&self) -> u64 {
+        u64::from(self.listing.price_per_item) * u64::from(self.amount)
+    }
+}
+
+/// The cheapest way to buy `amount` of one item, across whatever worlds we have listings for
+#[derive(Debug, Clone)]
+pub struct ItemPlan<'a> {
+    pub item_id: ItemId,
+    pub amount: u32,
+    /// empty if there weren't enough listings
+    pub purchases: Vec<Purchase<'a>>,
+    /// what buying it all on the home world would cost, if it has enough listings
+    pub home_world_cost: Option<u64>,
+}
+
+impl ItemPlan<'_> {
+    pub fn cost(&self) -> Option<u64> {
+        if self.purchases.is_empty() {
+            None
+        } else {
+            Some(self.purchases.iter().map(Purchase::cost).sum())
+        }
+    }
+
+    pub fn worlds(&self) -> impl Iterator<Item = &str> {
+        self.purchases
+            .iter()
+            .map(|p| p.listing.world.as_str())
+            .unique()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PurchasePlan<'a> {
+    pub items: Vec<ItemPlan<'a>>,
+    /// every purchase, grouped by the world it's on. The home world comes first,
+    /// then the rest with the most spent first
+    pub visits: Vec<(&'a str, Vec<Purchase<'a>>)>,
+}
+
+impl PurchasePlan<'_> {
+    pub fn total(&self) -> u64 {
+        self.items.iter().filter_map(ItemPlan::cost).sum()
+    }
+
+    /// How much less we spend than buying on the home world, counting only the items
+    /// the home world has enough of. `None` if it doesn't have enough of any of them, eg
+    /// because it isn't in the data center we looked at
+    pub fn saved_versus_home_world(&self) -> Option<i64> {
+        let savings = self
+            .items
+            .iter()
+            .filter_map(|i| Some(i.home_world_cost? as i64 - i.cost()? as i64))
+            .collect_vec();
+        (!savings.is_empty()).then(|| savings.iter().sum())
+    }
+}
+
+/// Works out where to buy each `(item, amount)`, buying from the cheapest listings first
+/// like `price_up_to` does, wherever they are. Items wanted more than once are bought together
+pub fn plan_purchases<'a>(
+    wanted: &[(ItemId, u32)],
+    market_data: &'a HashMap<ItemId, ItemMarketData>,
+    home_world: &'a str,
+) -> PurchasePlan<'a> {
+    // each item only has one set of listings, so plan the total rather than each request
+    let mut totals: Vec<(ItemId, u32)> = vec![];
+    for &(item_id, amount) in wanted {
+        match totals.iter_mut().find(|(id, _)| *id == item_id) {
+            Some((_, total)) => *total += amount,
+            None => totals.push((item_id, amount)),
+        }
+    }
+
+    let items = totals
+        .iter()
+        .map(|&(item_id, amount)| {
+            let listings = market_data
+                .get(&item_id)
+                .map(|md| md.listings.as_slice())
+                .unwrap_or_default();
+            let purchases = listings_up_to(listings, amount, false)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(listing, amount)| Purchase {
+                    item_id,
+                    listing,
+                    amount,
+                })
+                .collect_vec();
+            let home_listings = listings.iter().filter(|l| l.world == home_world);
+            let home_world_cost = listings_up_to(home_listings, amount, false)
+                .ok()
+                .map(|bought| {
+                    bought
+                        .iter()
+                        .map(|(l, amount)| u64::from(l.price_per_item) * u64::from(*amount))
+                        .sum()
+                });
+            ItemPlan {
+                item_id,
+                amount,
+                purchases,
+                home_world_cost,
+            }
+        })
+        .collect_vec();
+
+    let mut visits: Vec<(&str, Vec<Purchase>)> = vec![];
+    for purchase in items.iter().flat_map(|i| &i.purchases) {
+        let world = purchase.listing.world.as_str();
+        match visits.iter_mut().find(|(w, _)| *w == world) {
+            Some((_, purchases)) => purchases.push(purchase.clone()),
+            None => visits.push((world, vec![purchase.clone()])),
+        }
+    }
+    visits.sort_by_key(|(world, purchases)| {
+        let spent: u64 = purchases.iter().map(Purchase::cost).sum();
+        (*world != home_world, Reverse(spent))
+    });
+
+    PurchasePlan { items, visits }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn listing(price: u32, amount: u32, world: &str) -> ItemMarketListing {
+        ItemMarketListing::new(price, amount, false, world.to_string())
+    }
+
+    fn market_data(item_id: ItemId, listings: Vec<ItemMarketListing>) -> ItemMarketData {
+        ItemMarketData::new(item_id, Utc::now(), listings, vec![])
+    }
+
+    #[test]
+    fn buys_across_worlds_and_groups_by_world() {
+        let ore = ItemId::new(1);
+        let ingot = ItemId::new(2);
+        let data = HashMap::from([
+            (
+                ore,
+                market_data(
+                    ore,
+                    vec![
+                        listing(10, 5, "Phoenix"),
+                        listing(12, 5, "Alpha"),
+                        listing(20, 99, "Alpha"),
+                    ],
+                ),
+            ),
+            (ingot, market_data(ingot, vec![listing(100, 1, "Odin")])),
+        ]);
+
+        let plan = plan_purchases(&[(ore, 8), (ingot, 2)], &data, "Alpha");
+
+        // 5 from Phoenix, then 3 more from the cheaper Alpha listing
+        assert_eq!(plan.items[0].cost(), Some(50 + 36));
+        assert_eq!(
+            plan.items[0].worlds().collect_vec(),
+            vec!["Phoenix", "Alpha"]
+        );
+        // vs 5 at 12 and 3 at 20 on Alpha
+        assert_eq!(plan.items[0].home_world_cost, Some(60 + 60));
+        // not enough ingots anywhere
+        assert_eq!(plan.items[1].cost(), None);
+
+        assert_eq!(plan.total(), 86);
+        assert_eq!(plan.saved_versus_home_world(), Some(34));
+        assert_eq!(
+            plan.visits.iter().map(|(w, p)| (*w, p.len())).collect_vec(),
+            vec![("Alpha", 1), ("Phoenix", 1)]
+        );
+    }
+
+    #[test]
+    fn no_comparison_without_home_world_listings() {
+        let ore = ItemId::new(1);
+        let data = HashMap::from([(ore, market_data(ore, vec![listing(10, 5, "Cerberus")]))]);
+
+        let plan = plan_purchases(&[(ore, 5)], &data, "Alpha");
+
+        assert_eq!(plan.total(), 50);
+        assert_eq!(plan.items[0].home_world_cost, None);
+        assert_eq!(plan.saved_versus_home_world(), None);
+    }
+
+    #[test]
+    fn repeated_items_share_their_listings() {
+        let ore = ItemId::new(1);
+        let data = HashMap::from([(
+            ore,
+            market_data(ore, vec![listing(10, 5, "Alpha"), listing(20, 5, "Alpha")]),
+        )]);
+
+        let plan = plan_purchases(&[(ore, 5), (ore, 3)], &data, "Alpha");
+
+        assert_eq!(plan.items.len(), 1);
+        assert_eq!(plan.items[0].amount, 8);
+        // all of the cheap stack, then 3 of the other one, rather than the cheap stack twice
+        assert_eq!(plan.total(), 50 + 60);
+        assert_eq!(plan.visits[0].1.len(), 2);
+    }
+}
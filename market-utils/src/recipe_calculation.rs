@@ -5,13 +5,14 @@ use crate::{
 use chrono::{DateTime, Utc};
 use colored::Colorize;
 use ff14_data::{lookup::*, model::*};
-use std::{cmp::min, collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display};
 use thousands::Separable;
 
 pub struct LineItem {
     indent: usize,
     pub name_and_amount: String,
     pub amount: u32,
+    pub vendor_price: Option<u32>,
     pub market_price: Option<u32>,
     pub market_price_age: Option<DateTime<Utc>>,
     pub crafting_price: Option<u32>,
@@ -23,20 +24,81 @@ pub fn process_recipe_item(
     indent: usize,
     ri: &RecipeItem,
     items: &ItemLookup,
+    vendors: &VendorLookup,
+    market_data: &HashMap<ItemId, ItemMarketData>,
+    recipes: &RecipeLookup,
+    require_hq: bool,
+) -> (u32, Vec<LineItem>) {
+    let recipe = recipes.recipe_for_item(ri.item_id);
+    process_item(
+        indent,
+        ri,
+        recipe,
+        items,
+        vendors,
+        market_data,
+        recipes,
+        require_hq,
+    )
+}
+
+/// Like `process_recipe_item` for the result of `recipe`, but crafted with `recipe` itself
+/// rather than the first recipe that makes the result
+pub fn process_recipe(
+    indent: usize,
+    recipe: &Recipe,
+    items: &ItemLookup,
+    vendors: &VendorLookup,
+    market_data: &HashMap<ItemId, ItemMarketData>,
+    recipes: &RecipeLookup,
+    require_hq: bool,
+) -> (u32, Vec<LineItem>) {
+    process_item(
+        indent,
+        &recipe.result,
+        Some(recipe),
+        items,
+        vendors,
+        market_data,
+        recipes,
+        require_hq,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn process_item(
+    indent: usize,
+    ri: &RecipeItem,
+    recipe: Option<&Recipe>,
+    items: &ItemLookup,
+    vendors: &VendorLookup,
     market_data: &HashMap<ItemId, ItemMarketData>,
     recipes: &RecipeLookup,
     require_hq: bool,
 ) -> (u32, Vec<LineItem>) {
     let md = market_data.get(&ri.item_id);
     let i = items.item_by_id(ri.item_id);
+    // NPCs only sell NQ items
+    let vendor_price = vendors
+        .vendor_price(i)
+        .filter(|_| !(require_hq && i.can_be_hq))
+        .map(|p| p.saturating_mul(ri.amount));
     let market_price =
         md.and_then(|md| price_up_to(&md.listings, ri.amount, require_hq && i.can_be_hq).ok());
-    let crafting_results = recipes.recipe_for_item(ri.item_id).map(|sub_recipe| {
+    let crafting_results = recipe.map(|sub_recipe| {
         (match_recipe_to_output_count(ri.amount, sub_recipe))
             .ingredients
             .iter()
             .map(|sub_ri| {
-                process_recipe_item(indent + 2, sub_ri, items, market_data, recipes, require_hq)
+                process_recipe_item(
+                    indent + 2,
+                    sub_ri,
+                    items,
+                    vendors,
+                    market_data,
+                    recipes,
+                    require_hq,
+                )
             })
             .fold(
                 (0, vec![]),
@@ -55,7 +117,8 @@ pub fn process_recipe_item(
     crafting_lines.push(LineItem {
         indent,
         amount: ri.amount,
-        name_and_amount: format_recipe_item(ri, i, require_hq),
+        vendor_price,
+        name_and_amount: format_recipe_item(ri, i, items, require_hq),
         market_price,
         market_price_age: md.map(|md| md.last_upload_time),
         crafting_price,
@@ -63,15 +126,17 @@ pub fn process_recipe_item(
         item_id: ri.item_id,
     });
 
-    let lower_price = min(
-        market_price.unwrap_or(u32::MAX),
-        crafting_price.unwrap_or(u32::MAX),
-    );
+    let lower_price = [vendor_price, market_price, crafting_price]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(u32::MAX);
 
     if lower_price == u32::MAX {
         eprintln!(
             "WARN: No price found for {} {}. There may not be enough on the marketboard",
-            ri.amount, i.name_plural
+            ri.amount,
+            items.display_name(i, ri.amount)
         );
     }
 
@@ -93,6 +158,10 @@ pub fn print_recipe_calculation(mut lines: Vec<LineItem>) {
 }
 
 pub fn print_line_item(line: &LineItem) {
+    let vendor_price_str = line
+        .vendor_price
+        .map(|p| format!("V:{}", p.separate_with_commas()))
+        .unwrap_or_default();
     let market_price_str = line
         .market_price
         .map(|p| {
@@ -114,7 +183,13 @@ pub fn print_line_item(line: &LineItem) {
         .map(|p| format_num_diff(p).to_string())
         .unwrap_or(String::new());
 
-    let price_display = vec![market_price_str, crafting_price_str, diff_str].join(" ");
+    let price_display = [
+        vendor_price_str,
+        market_price_str,
+        crafting_price_str,
+        diff_str,
+    ]
+    .join(" ");
 
     println!(
         "{}{} {}",
@@ -125,13 +200,7 @@ pub fn print_line_item(line: &LineItem) {
 }
 
 pub fn match_recipe_to_output_count(output_count: u32, original_recipe: &Recipe) -> Recipe {
-    let recipe_count = div_ceil(output_count, original_recipe.result.amount);
-    original_recipe * recipe_count
-}
-
-fn div_ceil(a: u32, b: u32) -> u32 {
-    // https://stackoverflow.com/a/72442854
-    (a + b - 1) / b
+    original_recipe * original_recipe.crafts_for(output_count)
 }
 
 fn format_num_diff(value: i64) -> impl Display {
@@ -142,15 +211,11 @@ fn format_num_diff(value: i64) -> impl Display {
     }
 }
 
-fn format_recipe_item(ri: &RecipeItem, i: &Item, require_hq: bool) -> String {
+fn format_recipe_item(ri: &RecipeItem, i: &Item, items: &ItemLookup, require_hq: bool) -> String {
     format!(
         "{} {}{}",
         ri.amount,
-        if ri.amount > 1 {
-            &i.name_plural
-        } else {
-            &i.name_singular
-        },
+        items.display_name(i, ri.amount),
         if i.can_be_hq && require_hq {
             " (HQ)"
         } else {
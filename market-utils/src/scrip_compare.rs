@@ -1,56 +1,76 @@
-﻿use crate::recipe_calculation::process_recipe_item;
+﻿use crate::config::Config;
+use crate::recipe_calculation::process_recipe_item;
 use crate::universalis::get_market_data_lookup;
-use ff14_data::lookup::{ItemLookup, RecipeLookup};
-use ff14_data::model::Recipe;
+use derive_more::Constructor;
+use ff14_data::lookup::{
+    CurrencyExchangeLookup, GatheringLookup, ItemLookup, RecipeLookup, VendorLookup,
+};
+use ff14_data::model::{CollectableReward, GatheringItem, Item, ItemId, Language, Recipe};
 use itertools::Itertools;
+use std::cmp::Reverse;
 use thousands::Separable;
 
-fn scrip_per_item(ilvl: u32) -> u32 {
-    match ilvl {
-        // orange
-        690 => 144,
-        // purple
-        685 => 198,
-        675 => 171,
-        665 => 157,
-        656 => 142,
-        650 => 128,
-        560 => 114,
-        548 => 108,
-        _ => todo!("scrip per item for ilvl {}", ilvl),
-    }
-}
-
 pub async fn print_scrip_source_compare(
     items: &ItemLookup,
     recipes_lookup: &RecipeLookup,
     recipes: Vec<&Recipe>,
+    currency: ItemId,
     target_scrip_count: u32,
+    config: &Config,
 ) -> color_eyre::Result<()> {
     let all_ids = recipes
         .iter()
         .flat_map(|r| r.relevant_item_ids(&recipes_lookup))
         .collect_vec();
-    let market_data = get_market_data_lookup(&all_ids).await?;
+    let market_data = get_market_data_lookup(&all_ids, config).await?;
+    let vendors = VendorLookup::from_embedded()?;
+    let gathering = GatheringLookup::from_embedded()?;
 
     let result_lines = recipes
         .iter()
-        .map(|&r| {
+        .filter_map(|&r| {
+            let reward = gathering.collectable_reward(r.result.item_id);
+            if reward.is_none() {
+                eprintln!(
+                    "Skipping {}: no collectables appraiser reward for it",
+                    items.item_by_id(r.result.item_id).name
+                );
+            }
+            // collectables for other scrips can't be compared with these
+            let reward = reward.filter(|reward| reward.currency == currency)?;
+            Some((r, reward.max_reward()))
+        })
+        .filter(|&(_, scrip_per_item)| scrip_per_item > 0)
+        .map(|(r, scrip_per_item)| {
             // make enough copies of the recipe to get the target scrip count
-            let scrip_per_item = scrip_per_item(items.item_by_id(r.result.item_id).ilvl);
             // https://stackoverflow.com/a/2745086
             let recipe_multiplier = (target_scrip_count + scrip_per_item - 1) / scrip_per_item;
             assert!(recipe_multiplier > 0, "recipe multiplier must be > 0");
-            r * recipe_multiplier
+            (r.job, scrip_per_item, r * recipe_multiplier)
         })
-        .map(|r| process_recipe_item(0, &r.result, &items, &market_data, &recipes_lookup, false).1)
-        .map(|r| r.into_iter().last().unwrap())
-        .map(|l| {
-            let scrip_value = scrip_per_item(items.item_by_id(l.item_id).ilvl);
+        .map(|(job, scrip_value, r)| {
+            (
+                job,
+                scrip_value,
+                process_recipe_item(
+                    0,
+                    &r.result,
+                    &items,
+                    &vendors,
+                    &market_data,
+                    &recipes_lookup,
+                    false,
+                )
+                .1,
+            )
+        })
+        .map(|(job, scrip_value, r)| (job, scrip_value, r.into_iter().last().unwrap()))
+        .map(|(job, scrip_value, l)| {
             let crafting_price = l.crafting_price.expect("crafting price");
             let cost = crafting_price / l.amount / scrip_value;
             let text = format!(
-                "{:<50}: {} or ~{} per scrip",
+                "{} {:<50}: {} or ~{} per scrip",
+                job.abbreviation(),
                 l.name_and_amount,
                 crafting_price.separate_with_commas(),
                 cost.separate_with_commas()
@@ -66,43 +86,234 @@ pub async fn print_scrip_source_compare(
     Ok(())
 }
 
-pub async fn print_script_sink_compare(items: &[(u32, &'static str)], target_scrip_count: u32) {
-    let items_lookup = ItemLookup::from_embedded()
-        .expect("Failed to load item data");
+/// Like `print_scrip_source_compare`, but for gathered collectables. Gathering
+/// a collectable means not gathering the normal version of the item, so we use
+/// what the normal version sells for as the cost of the scrips.
+pub async fn print_gathering_scrip_source_compare(
+    items: &ItemLookup,
+    gathering: &GatheringLookup,
+    collectables: Vec<&CollectableReward>,
+    gathers_per_hour: u32,
+    config: &Config,
+) -> color_eyre::Result<()> {
+    let collectables = collectables
+        .into_iter()
+        .filter_map(|c| {
+            let item = items.item_by_id_opt(c.item_id)?;
+            let job = gathering.points_for(c.item_id).next()?.gathering_type;
+            let normal = gathering
+                .gathering_items_for(c.item_id)
+                .find_map(|gi| {
+                    normal_version(gi, gathering.gathered_alongside(c.item_id), |id| {
+                        items
+                            .item_by_id_opt(id)
+                            .is_some_and(|i| !i.is_collectable && i.is_marketable())
+                    })
+                })
+                .map(|id| items.item_by_id(id));
+            Some((c, item, job, normal))
+        })
+        .collect_vec();
+    let normal_ids = collectables
+        .iter()
+        .filter_map(|(_, _, _, normal)| normal.map(|n| n.id))
+        .collect_vec();
+    let market_data = get_market_data_lookup(&normal_ids, config).await?;
 
-    let items = items
+    let result_lines = collectables
         .iter()
-        .filter_map(|(scrip, name)| Some(*scrip).zip(items_lookup.item_by_name_opt(name)))
+        .map(|(c, item, job, normal)| {
+            let scrips_per_hour = c.max_reward() * gathers_per_hour;
+            let gil_per_hour = normal
+                .and_then(|n| market_data.get(&n.id))
+                .and_then(|data| data.listings.first().map(|listing| listing.price_per_item))
+                .map(|p| p * gathers_per_hour);
+            match (normal, gil_per_hour) {
+                (Some(normal), Some(gil)) => {
+                    let cost = gil / scrips_per_hour.max(1);
+                    let text = format!(
+                        "{} {:<50}: {} scrips/h, instead of ~{} gil/h from {} or ~{} per scrip",
+                        job.abbreviation(),
+                        item.name,
+                        scrips_per_hour.separate_with_commas(),
+                        gil.separate_with_commas(),
+                        normal.name,
+                        cost.separate_with_commas()
+                    );
+                    GatheringScripLine::new(scrips_per_hour, Some(cost), text)
+                }
+                _ => {
+                    let text = format!(
+                        "{} {:<50}: {} scrips/h, normal version price not available",
+                        job.abbreviation(),
+                        item.name,
+                        scrips_per_hour.separate_with_commas()
+                    );
+                    GatheringScripLine::new(scrips_per_hour, None, text)
+                }
+            }
+        })
         .collect_vec();
-    let item_ids = items.iter().map(|(_, item)| (*item).id).collect_vec();
 
-    let market_data = get_market_data_lookup(&item_ids)
-        .await
-        .expect("Failed to fetch market data");
+    for line in rank_gathering_lines(result_lines) {
+        println!("{}", line.text);
+    }
+    Ok(())
+}
 
-    let mut results = items
+/// What we'd gather instead of `collectable`: something else on the same points, at
+/// the same level, that `is_normal` (eg isn't a collectable itself)
+fn normal_version<'a>(
+    collectable: &GatheringItem,
+    alongside: impl IntoIterator<Item = &'a GatheringItem>,
+    is_normal: impl Fn(ItemId) -> bool,
+) -> Option<ItemId> {
+    alongside
         .into_iter()
-        .map(|(scrip_cost, item)| {
-            let item_count = target_scrip_count / scrip_cost;
-            let prices = market_data.get(&item.id);
+        .filter(|gi| gi.level == collectable.level && gi.item_id != collectable.item_id)
+        .map(|gi| gi.item_id)
+        .find(|&id| is_normal(id))
+}
+
+#[derive(Debug, Constructor)]
+struct GatheringScripLine {
+    scrips_per_hour: u32,
+    /// gil per scrip we give up by not gathering the normal version, if it has a price
+    cost: Option<u32>,
+    text: String,
+}
+
+/// Most scrips per hour first, then the cheapest scrips, with anything we couldn't price last
+fn rank_gathering_lines(lines: Vec<GatheringScripLine>) -> Vec<GatheringScripLine> {
+    lines
+        .into_iter()
+        .sorted_by_key(|l| (Reverse(l.scrips_per_hour), l.cost.is_none(), l.cost))
+        .collect_vec()
+}
+
+/// Everything marketable we can buy with any of `currencies` (eg "Orange Crafters' Scrip"),
+/// and roughly how much gil `target_scrip_count` of the currency turns into for each item
+pub async fn print_script_sink_compare(
+    currencies: &[&str],
+    target_scrip_count: u32,
+    config: &Config,
+) -> color_eyre::Result<()> {
+    let items_lookup = ItemLookup::from_embedded()?;
+    let exchanges = CurrencyExchangeLookup::from_embedded()?;
+
+    let currencies = currencies
+        .iter()
+        .map(|name| items_lookup.find_item_by_name(name, Language::English))
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    let sinks = currencies
+        .iter()
+        .flat_map(|currency| {
+            exchanges
+                .exchanges_costing(currency.id)
+                .filter_map(|e| match e.receive.as_slice() {
+                    [receive] => Some((e.cost[0].amount, receive, *currency)),
+                    _ => None,
+                })
+        })
+        .filter_map(|(cost, receive, currency)| {
+            let item = items_lookup.item_by_id_opt(receive.item_id)?;
+            item.is_marketable().then_some(SinkItem::new(
+                cost,
+                receive.amount,
+                item,
+                Some(currency),
+            ))
+        })
+        // the same trade is usually available from several shops
+        .sorted_by_key(|s| (s.item.id, s.currency.map(|c| c.id), s.cost))
+        .dedup_by(|a, b| {
+            a.item.id == b.item.id && a.currency.map(|c| c.id) == b.currency.map(|c| c.id)
+        })
+        .collect_vec();
+
+    print_sink_compare(&sinks, target_scrip_count, config).await
+}
+
+/// Like `print_script_sink_compare`, for currencies we can't find the shops for
+pub async fn print_item_sink_compare(
+    items: &[(u32, &'static str)],
+    target_scrip_count: u32,
+    config: &Config,
+) -> color_eyre::Result<()> {
+    let items_lookup = ItemLookup::from_embedded()?;
+
+    let sinks = items
+        .iter()
+        .filter_map(|(scrip, name)| {
+            Some(SinkItem::new(
+                *scrip,
+                1,
+                items_lookup.item_by_name_opt(name)?,
+                None,
+            ))
+        })
+        .collect_vec();
+
+    print_sink_compare(&sinks, target_scrip_count, config).await
+}
+
+#[derive(Constructor)]
+struct SinkItem<'a> {
+    cost: u32,
+    /// how many of the item we get for `cost`
+    amount: u32,
+    item: &'a Item,
+    currency: Option<&'a Item>,
+}
+
+async fn print_sink_compare(
+    sinks: &[SinkItem<'_>],
+    target_scrip_count: u32,
+    config: &Config,
+) -> color_eyre::Result<()> {
+    let item_ids = sinks.iter().map(|s| s.item.id).collect_vec();
+    let show_currency = sinks
+        .iter()
+        .map(|s| s.currency.map(|c| c.id))
+        .unique()
+        .count()
+        > 1;
+
+    let market_data = get_market_data_lookup(&item_ids, config).await?;
+
+    let mut results = sinks
+        .iter()
+        .map(|s| {
+            let item_count = target_scrip_count / s.cost * s.amount;
+            let prices = market_data.get(&s.item.id);
             // let buy_price = prices.and_then(|data| price_up_to(&data.listings, item_count, false).ok());
             // for the purposes of selling, though, we're going to undercut
             // whatever the cheapest price currently is
             let sell_price = prices
                 .and_then(|data| data.listings.first().map(|listing| listing.price_per_item))
                 .map(|p| p * item_count);
+            // only worth mentioning if we're comparing several currencies
+            let currency = match s.currency {
+                Some(c) if show_currency => format!(" [{}]", c.name),
+                _ => String::new(),
+            };
             match sell_price {
                 Some(p) => (
                     p / target_scrip_count,
                     format!(
-                        "{}x {}: {} gil (~{} per scrip)",
+                        "{}x {}: {} gil (~{} per scrip){}",
                         item_count,
-                        item.name,
+                        s.item.name,
                         p.separate_with_commas(),
-                        (p / target_scrip_count).separate_with_commas()
+                        (p / target_scrip_count).separate_with_commas(),
+                        currency
                     ),
                 ),
-                None => (0, format!("{}: Price not available", item.name)),
+                None => (
+                    0,
+                    format!("{}: Price not available{}", s.item.name, currency),
+                ),
             }
         })
         .collect_vec();
@@ -112,4 +323,59 @@ pub async fn print_script_sink_compare(items: &[(u32, &'static str)], target_scr
     for (_, line) in results {
         println!("{}", line);
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff14_data::model::GatheringItemId;
+
+    fn gathering_item(id: i32, item_id: i32, level: u16) -> GatheringItem {
+        GatheringItem::new(
+            GatheringItemId::new(id),
+            ItemId::new(item_id),
+            level,
+            0,
+            false,
+        )
+    }
+
+    #[test]
+    fn normal_version_is_a_non_collectable_at_the_same_level() {
+        let rarefied = gathering_item(1, 100, 90);
+        let alongside = [
+            gathering_item(2, 101, 80),
+            gathering_item(3, 102, 90),
+            gathering_item(4, 103, 90),
+        ];
+        let not_collectable = |id: ItemId| id != ItemId::new(102);
+
+        assert_eq!(
+            normal_version(&rarefied, &alongside, not_collectable),
+            Some(ItemId::new(103))
+        );
+        assert_eq!(
+            normal_version(&rarefied, &alongside[..2], not_collectable),
+            None
+        );
+    }
+
+    #[test]
+    fn ranks_by_scrips_per_hour_then_cost_with_unpriced_last() {
+        let line = |scrips_per_hour, cost, text: &str| {
+            GatheringScripLine::new(scrips_per_hour, cost, text.to_string())
+        };
+        let ranked = rank_gathering_lines(vec![
+            line(1_000, Some(5), "slow"),
+            line(2_000, None, "fast, unpriced"),
+            line(2_000, Some(30), "fast, pricey"),
+            line(2_000, Some(10), "fast, cheap"),
+        ]);
+
+        assert_eq!(
+            ranked.iter().map(|l| l.text.as_str()).collect_vec(),
+            vec!["fast, cheap", "fast, pricey", "fast, unpriced", "slow"]
+        );
+    }
 }
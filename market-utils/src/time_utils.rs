@@ -1,4 +1,5 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, Local, Utc};
+use std::fmt;
 
 pub fn hm_ago(dur: Duration) -> String {
     if dur.num_minutes() < 1 {
@@ -13,3 +14,161 @@ pub fn hm_ago(dur: Duration) -> String {
 pub fn hm_ago_from_now(t: DateTime<Utc>) -> String {
     hm_ago(Utc::now() - t)
 }
+
+/// eg "1h5m", or just "5m" if it's less than an hour
+pub fn hm(dur: Duration) -> String {
+    if dur.num_hours() > 0 {
+        format!("{}h{}m", dur.num_hours(), dur.num_minutes() % 60)
+    } else {
+        format!("{}m", dur.num_minutes())
+    }
+}
+
+/// HH:MM in local time
+pub fn local_hm(t: DateTime<Utc>) -> String {
+    t.with_timezone(&Local).format("%H:%M").to_string()
+}
+
+// measured on the Eorzean clock (ff14_data::weather has real seconds per bell)
+const ET_SECONDS_PER_BELL: i64 = 60 * 60;
+const ET_SECONDS_PER_DAY: i64 = 24 * ET_SECONDS_PER_BELL;
+
+/// A time on the Eorzean clock, which runs 3600/175 (= 144/7) times faster than real time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EorzeaTime {
+    /// Eorzean seconds since the unix epoch
+    seconds: i64,
+}
+
+impl EorzeaTime {
+    pub fn now() -> EorzeaTime {
+        EorzeaTime::from_utc(Utc::now())
+    }
+
+    pub fn from_utc(t: DateTime<Utc>) -> EorzeaTime {
+        EorzeaTime {
+            seconds: (t.timestamp_millis() * 144).div_euclid(7 * 1000),
+        }
+    }
+
+    /// The first real time that shows this Eorzean time
+    pub fn to_utc(self) -> DateTime<Utc> {
+        let millis = (self.seconds * 7 * 1000 + 143).div_euclid(144);
+        DateTime::from_timestamp_millis(millis).expect("eorzea time in range")
+    }
+
+    /// eg `from_clock(0, 8, 0)` for 08:00 ET on the first Eorzean day after the unix epoch
+    pub fn from_clock(day: i64, bell: u32, minute: u32) -> EorzeaTime {
+        EorzeaTime {
+            seconds: day * ET_SECONDS_PER_DAY
+                + i64::from(bell) * ET_SECONDS_PER_BELL
+                + i64::from(minute) * 60,
+        }
+    }
+
+    pub fn day(self) -> i64 {
+        self.seconds.div_euclid(ET_SECONDS_PER_DAY)
+    }
+
+    pub fn bell(self) -> u32 {
+        (self.seconds.rem_euclid(ET_SECONDS_PER_DAY) / ET_SECONDS_PER_BELL) as u32
+    }
+
+    pub fn minute(self) -> u32 {
+        (self.seconds.rem_euclid(ET_SECONDS_PER_BELL) / 60) as u32
+    }
+
+    pub fn add_minutes(self, minutes: i64) -> EorzeaTime {
+        EorzeaTime {
+            seconds: self.seconds + minutes * 60,
+        }
+    }
+
+    /// The next time the clock shows `bell:minute`, which might be now
+    pub fn next_at(self, bell: u32, minute: u32) -> EorzeaTime {
+        let today = EorzeaTime::from_clock(self.day(), bell, minute);
+        if today >= self {
+            today
+        } else {
+            EorzeaTime::from_clock(self.day() + 1, bell, minute)
+        }
+    }
+
+    /// (start, end) of each `bells`-long window, starting with the one we're in now.
+    /// Windows line up with midnight, so `windows(8)` gives the weather periods.
+    pub fn windows(self, bells: u32) -> impl Iterator<Item = (EorzeaTime, EorzeaTime)> {
+        let length = i64::from(bells) * ET_SECONDS_PER_BELL;
+        let first = self.seconds - self.seconds.rem_euclid(length);
+        (0..).map(move |i| {
+            let start = first + i * length;
+            (
+                EorzeaTime { seconds: start },
+                EorzeaTime {
+                    seconds: start + length,
+                },
+            )
+        })
+    }
+
+    /// An in-game alarm that goes off every day at this Eorzean time
+    pub fn alarm(self, name: &str, minutes_before: u32) -> String {
+        format!(
+            r#"/alarm "{}" et {:02}{:02} {} se00"#,
+            name,
+            self.bell(),
+            self.minute(),
+            minutes_before
+        )
+    }
+}
+
+impl fmt::Display for EorzeaTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02} ET", self.bell(), self.minute())
+    }
+}
+
+/// An in-game alarm for a real time. Alarms use server time, which is UTC
+pub fn server_alarm(name: &str, t: DateTime<Utc>, minutes_before: u32) -> String {
+    format!(
+        r#"/alarm "{}" st {} {} se00"#,
+        name,
+        t.format("%H%M"),
+        minutes_before
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff14_data::weather::SECONDS_PER_BELL;
+
+    #[test]
+    fn a_bell_is_175_seconds() {
+        let start = EorzeaTime::from_clock(100, 8, 0);
+        let later = EorzeaTime::from_utc(start.to_utc() + Duration::seconds(SECONDS_PER_BELL));
+        assert_eq!(EorzeaTime::from_clock(100, 9, 0), later);
+        assert_eq!(start, EorzeaTime::from_utc(start.to_utc()));
+    }
+
+    #[test]
+    fn windows_line_up_with_midnight() {
+        let t = EorzeaTime::from_clock(3, 9, 30);
+        let windows = t.windows(8).take(2).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    EorzeaTime::from_clock(3, 8, 0),
+                    EorzeaTime::from_clock(3, 16, 0)
+                ),
+                (
+                    EorzeaTime::from_clock(3, 16, 0),
+                    EorzeaTime::from_clock(4, 0, 0)
+                ),
+            ],
+            windows
+        );
+        assert_eq!(EorzeaTime::from_clock(4, 2, 0), t.next_at(2, 0));
+        assert_eq!(r#"/alarm "Node" et 0930 5 se00"#, t.alarm("Node", 5));
+    }
+}
@@ -1,9 +1,10 @@
+use crate::config::Config;
 use chrono::{DateTime, TimeZone, Utc};
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use derive_more::Constructor;
 use ff14_data::model::*;
 use itertools::Itertools;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use std::{cmp, collections::HashMap};
 
@@ -20,6 +21,8 @@ pub struct ItemMarketListing {
     pub price_per_item: u32,
     pub amount: u32,
     pub hq: bool,
+    /// which world it's listed on, which only varies if we asked for a data center or region
+    pub world: String,
 }
 
 #[derive(Debug, Constructor)]
@@ -29,15 +32,22 @@ pub struct ItemMarketHistory {
     pub hq: bool,
 }
 
+/// What we get back when asking for more than one item
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct UniversalisMarketDataJson {
     pub items: HashMap<i32, UniversalisMarketDataItemJson>,
+    /// ids Universalis has no data for (eg untradable items), which are left out of `items`
+    #[serde(default)]
+    pub unresolved_items: Vec<i32>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct UniversalisMarketDataItemJson {
     last_upload_time: i64,
+    /// only there if we asked for a single world
+    world_name: Option<String>,
     listings: Vec<UniversalisMarketListingJson>,
     recent_history: Vec<UniversalisMarketHistoryJson>,
 }
@@ -47,6 +57,8 @@ struct UniversalisMarketListingJson {
     price_per_unit: u32,
     quantity: u32,
     hq: bool,
+    /// only there if we asked for a data center or region
+    world_name: Option<String>,
 }
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -57,46 +69,92 @@ struct UniversalisMarketHistoryJson {
     on_mannequin: bool,
 }
 
-pub async fn get_market_data(ids: &[ItemId]) -> Result<Vec<ItemMarketData>> {
-    assert!(ids.len() > 1, "Universalis gives us results in a different format if we only query a single item, and we don't currently cope with that");
+const MARKET_DATA_FIELDS: [&str; 10] = [
+    "lastUploadTime",
+    "worldName",
+    "listings.worldName",
+    "listings.quantity",
+    "listings.pricePerUnit",
+    "listings.hq",
+    "recentHistory.quantity",
+    "recentHistory.pricePerUnit",
+    "recentHistory.hq",
+    "recentHistory.onMannequin",
+];
+
+/// Items Universalis doesn't know about are left out of the result
+pub async fn get_market_data(ids: &[ItemId], config: &Config) -> Result<Vec<ItemMarketData>> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
 
     let base = "https://universalis.app/api/v2";
-    let world = "Alpha";
-    let ids = ids.iter().map(|x| format!("{x}")).collect_vec().join(",");
+    let market = config.market();
+    // a single item comes back on its own rather than inside `items`, so its fields aren't prefixed
+    let fields = match ids {
+        [_] => MARKET_DATA_FIELDS.join(","),
+        _ => MARKET_DATA_FIELDS
+            .iter()
+            .map(|f| format!("items.{f}"))
+            .chain(["unresolvedItems".to_string()])
+            .join(","),
+    };
+    let id_list = ids.iter().map(|x| format!("{x}")).collect_vec().join(",");
     let client = Client::new();
     let response = client
-        .get(format!("{base}/{world}/{ids}"))
+        .get(format!("{base}/{market}/{id_list}"))
         .query(&[("entries", 0)])
-        .query(&[(
-            "fields",
-            [
-                "items.lastUploadTime",
-                "items.listings.quantity",
-                "items.listings.pricePerUnit",
-                "items.listings.hq",
-                "items.recentHistory.quantity",
-                "items.recentHistory.pricePerUnit",
-                "items.recentHistory.hq",
-                "items.recentHistory.onMannequin",
-            ]
-            .join(","),
-        )])
+        .query(&[("fields", fields)])
         .send()
-        .await?
-        .error_for_status()?
-        .json::<UniversalisMarketDataJson>()
         .await?;
+    // which is also why asking for a single unknown item is a 404 rather than an unresolved item
+    if ids.len() == 1 && response.status() == StatusCode::NOT_FOUND {
+        return Ok(vec![]);
+    }
+    let body = response.error_for_status()?.text().await?;
 
-    Ok(response
-        .items
+    parse_market_data(ids, &body, market)
+}
+
+fn parse_market_data(ids: &[ItemId], body: &str, market: &str) -> Result<Vec<ItemMarketData>> {
+    let items = match ids {
+        [id] => vec![(
+            (*id).into(),
+            serde_json::from_str::<UniversalisMarketDataItemJson>(body)?,
+        )],
+        _ => {
+            let response = serde_json::from_str::<UniversalisMarketDataJson>(body)?;
+            let missing = ids
+                .iter()
+                .map(|&id| Into::<i32>::into(id))
+                .filter(|id| {
+                    !response.items.contains_key(id) && !response.unresolved_items.contains(id)
+                })
+                .collect_vec();
+            if !missing.is_empty() {
+                return Err(eyre!("Universalis didn't return items {:?}", missing));
+            }
+            response.items.into_iter().collect_vec()
+        }
+    };
+
+    Ok(items
         .iter()
-        .map(|(&k, v)| {
+        .map(|(k, v)| {
             ItemMarketData::new(
-                ItemId::new(k),
+                ItemId::new(*k),
                 Utc.timestamp_millis_opt(v.last_upload_time).unwrap(),
                 v.listings
                     .iter()
-                    .map(|l| ItemMarketListing::new(l.price_per_unit, l.quantity, l.hq))
+                    .map(|l| {
+                        let world = l.world_name.as_ref().or(v.world_name.as_ref());
+                        ItemMarketListing::new(
+                            l.price_per_unit,
+                            l.quantity,
+                            l.hq,
+                            world.map_or(market, |w| w.as_str()).to_string(),
+                        )
+                    })
                     .collect_vec(),
                 v.recent_history
                     .iter()
@@ -108,10 +166,15 @@ pub async fn get_market_data(ids: &[ItemId]) -> Result<Vec<ItemMarketData>> {
         .collect_vec())
 }
 
-pub async fn get_market_data_lookup<'a>(ids: &[ItemId]) -> Result<HashMap<ItemId, ItemMarketData>> {
+pub async fn get_market_data_lookup<'a>(
+    ids: &[ItemId],
+    config: &Config,
+) -> Result<HashMap<ItemId, ItemMarketData>> {
+    // callers often ask for the same item (eg crystals) more than once
+    let ids = ids.iter().copied().unique().collect_vec();
     let mut result = HashMap::new();
     for id_batch in ids.chunks(100) {
-        for item in get_market_data(id_batch).await? {
+        for item in get_market_data(id_batch, config).await? {
             result.insert(item.item_id, item);
         }
     }
@@ -127,10 +190,22 @@ pub fn price_up_to(
     amount_wanted: u32,
     hq_only: bool,
 ) -> Result<u32, String> {
+    Ok(listings_up_to(listings, amount_wanted, hq_only)?
+        .iter()
+        .map(|(listing, amount)| listing.price_per_item * amount)
+        .sum())
+}
+
+/// The listings `price_up_to` buys from, and how many from each
+pub fn listings_up_to<'a>(
+    listings: impl IntoIterator<Item = &'a ItemMarketListing>,
+    amount_wanted: u32,
+    hq_only: bool,
+) -> Result<Vec<(&'a ItemMarketListing, u32)>, String> {
     let mut amount_remaining = amount_wanted;
-    let mut cumulative_price = 0;
+    let mut bought = vec![];
     let mut listings = listings
-        .iter()
+        .into_iter()
         // https://stackoverflow.com/a/68522183
         .filter(|l| if hq_only { l.hq } else { true });
 
@@ -138,15 +213,15 @@ pub fn price_up_to(
         match listings.next() {
             None => break,
             Some(next_listing) => {
-                cumulative_price +=
-                    next_listing.price_per_item * (cmp::min(amount_remaining, next_listing.amount));
-                amount_remaining = amount_remaining.saturating_sub(next_listing.amount);
+                let amount = cmp::min(amount_remaining, next_listing.amount);
+                bought.push((next_listing, amount));
+                amount_remaining -= amount;
             }
         }
     }
     match amount_remaining {
         x if x > 0 => Err("Couldn't find enough listings to satisfy demand".to_string()),
-        _ => Ok(cumulative_price),
+        _ => Ok(bought),
     }
 }
 
@@ -158,9 +233,9 @@ mod tests {
     #[test]
     fn test_price_up_to() {
         let listings = vec![
-            ItemMarketListing::new(10, 1, false),
-            ItemMarketListing::new(20, 1, true),
-            ItemMarketListing::new(100, 10, false),
+            ItemMarketListing::new(10, 1, false, "Alpha".to_string()),
+            ItemMarketListing::new(20, 1, true, "Alpha".to_string()),
+            ItemMarketListing::new(100, 10, false, "Alpha".to_string()),
         ];
 
         // just buying the cheapest
@@ -182,4 +257,27 @@ mod tests {
             price_up_to(&listings, 20, false)
         );
     }
+
+    #[test]
+    fn parses_single_and_multiple_item_responses() {
+        let item = r#"{"lastUploadTime": 1000, "worldName": "Alpha",
+            "listings": [{"pricePerUnit": 5, "quantity": 2, "hq": false}],
+            "recentHistory": [{"pricePerUnit": 4, "quantity": 1, "hq": true, "onMannequin": false}]}"#;
+
+        let single = parse_market_data(&[ItemId::new(7)], item, "Alpha").unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].item_id, ItemId::new(7));
+        assert_eq!(single[0].listings[0].world, "Alpha");
+
+        let multiple = format!(r#"{{"items": {{"7": {item}}}, "unresolvedItems": [8]}}"#);
+        let multiple =
+            parse_market_data(&[ItemId::new(7), ItemId::new(8)], &multiple, "Light").unwrap();
+        assert_eq!(multiple.len(), 1);
+        assert_eq!(multiple[0].item_id, ItemId::new(7));
+        assert_eq!(multiple[0].history.len(), 1);
+
+        // 8 is neither listed nor unresolved
+        let partial = format!(r#"{{"items": {{"7": {item}}}}}"#);
+        assert!(parse_market_data(&[ItemId::new(7), ItemId::new(8)], &partial, "Light").is_err());
+    }
 }
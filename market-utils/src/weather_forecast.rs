@@ -0,0 +1,43 @@
+use crate::time_utils::{hm, local_hm, server_alarm, EorzeaTime};
+use chrono::{DateTime, Utc};
+use ff14_data::{lookup::WeatherLookup, model::Territory};
+
+fn to_utc(unix_seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(unix_seconds, 0).expect("timestamp in range")
+}
+
+/// eg "Current weather: Fair Skies (ends at 12:34 in 5m)"
+pub fn print_current_weather(weathers: &WeatherLookup, territory: &Territory, now: DateTime<Utc>) {
+    let Some(current) = weathers.weather_at(territory, now.timestamp()) else {
+        println!("Current weather: unknown");
+        return;
+    };
+    // weather changes every 8 bells
+    let (_, end) = EorzeaTime::from_utc(now)
+        .windows(8)
+        .next()
+        .expect("windows never end");
+    let end = end.to_utc();
+
+    println!(
+        "Current weather: {} (ends at {} in {})",
+        current.name,
+        local_hm(end),
+        hm(end - now)
+    );
+}
+
+/// Prints each upcoming event with an in-game `/alarm` for a minute before it starts
+pub fn print_upcoming(events: impl Iterator<Item = (i64, String)>, now: DateTime<Utc>) {
+    for (start, name) in events {
+        let start = to_utc(start);
+        println!(
+            "Next weather event: {} at {} (in {})",
+            name,
+            local_hm(start),
+            hm(start - now)
+        );
+        println!("{}", server_alarm(&format!("{} in 1m", name), start, 1));
+        println!();
+    }
+}